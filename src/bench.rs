@@ -0,0 +1,428 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_PATH: &str = "bench/history.tsv";
+const DEFAULT_RUNS: usize = 10;
+// Welch's t above this is treated as a real difference, roughly p < 0.01 for
+// the sample sizes used here
+const SIGNIFICANT_T: f64 = 3.0;
+// Slowdowns below this relative change are ignored even if significant
+const MIN_RELATIVE_CHANGE: f64 = 0.05;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Stats {
+    pub runs: usize,
+    pub mean_ns: f64,
+    pub stddev_ns: f64,
+    pub min_ns: f64,
+    pub median_ns: f64,
+    pub max_ns: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[f64]) -> Stats {
+        assert!(
+            !samples.is_empty(),
+            "Cannot compute statistics without samples"
+        );
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable_by(f64::total_cmp);
+
+        let runs = sorted.len();
+        let mean_ns = sorted.iter().sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            sorted.iter().map(|x| (x - mean_ns).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+        let median_ns = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2.0
+        } else {
+            sorted[runs / 2]
+        };
+
+        Stats {
+            runs,
+            mean_ns,
+            stddev_ns: variance.sqrt(),
+            min_ns: sorted[0],
+            median_ns,
+            max_ns: sorted[runs - 1],
+        }
+    }
+
+    /// Welch's t statistic of `self` against `baseline`, positive when `self` is slower.
+    pub fn welch_t(&self, baseline: &Stats) -> f64 {
+        let standard_error = (self.stddev_ns.powi(2) / self.runs as f64
+            + baseline.stddev_ns.powi(2) / baseline.runs as f64)
+            .sqrt();
+        let difference = self.mean_ns - baseline.mean_ns;
+        if standard_error == 0.0 {
+            // Noiseless runs, any difference at all is significant
+            if difference == 0.0 {
+                return 0.0;
+            }
+            return difference.signum() * f64::INFINITY;
+        }
+        difference / standard_error
+    }
+}
+
+/// One line of the history file: the statistics of one part in one benchmark run.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Record {
+    pub timestamp: u64,
+    pub commit: String,
+    pub machine: String,
+    pub day: u8,
+    pub part: u8,
+    pub stats: Stats,
+}
+
+impl std::fmt::Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = &self.stats;
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{:.0}\t{:.0}\t{:.0}\t{:.0}\t{:.0}",
+            self.timestamp,
+            self.commit,
+            self.machine,
+            self.day,
+            self.part,
+            s.runs,
+            s.mean_ns,
+            s.stddev_ns,
+            s.min_ns,
+            s.median_ns,
+            s.max_ns
+        )
+    }
+}
+
+impl FromStr for Record {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 11 {
+            return Err(format!("Expected 11 fields, found {}", fields.len()));
+        }
+        let number = |idx: usize| {
+            fields[idx]
+                .parse::<f64>()
+                .map_err(|err| format!("Field {} ({}): {}", idx + 1, fields[idx], err))
+        };
+        let integer = |idx: usize| number(idx).map(|x| x as u64);
+
+        Ok(Record {
+            timestamp: integer(0)?,
+            commit: fields[1].to_string(),
+            machine: fields[2].to_string(),
+            day: integer(3)? as u8,
+            part: integer(4)? as u8,
+            stats: Stats {
+                runs: integer(5)? as usize,
+                mean_ns: number(6)?,
+                stddev_ns: number(7)?,
+                min_ns: number(8)?,
+                median_ns: number(9)?,
+                max_ns: number(10)?,
+            },
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub day: u8,
+    pub part: u8,
+    pub baseline: Stats,
+    pub current: Stats,
+    pub t: f64,
+}
+
+impl Comparison {
+    pub fn relative_change(&self) -> f64 {
+        (self.current.mean_ns - self.baseline.mean_ns) / self.baseline.mean_ns
+    }
+
+    pub fn is_regression(&self) -> bool {
+        self.t > SIGNIFICANT_T && self.relative_change() > MIN_RELATIVE_CHANGE
+    }
+}
+
+/// Compares the most recent run in `history` against the latest earlier run on the
+/// same machine, or against the latest run of `baseline_commit` if one is given.
+pub fn compare(
+    history: &[Record],
+    baseline_commit: Option<&str>,
+) -> Result<Vec<Comparison>, String> {
+    let latest = history.last().ok_or("Benchmark history is empty")?;
+    let current: Vec<&Record> = history
+        .iter()
+        .filter(|r| r.timestamp == latest.timestamp && r.machine == latest.machine)
+        .collect();
+
+    let baseline_timestamp = history
+        .iter()
+        .filter(|r| r.machine == latest.machine && r.timestamp < latest.timestamp)
+        .filter(|r| baseline_commit.is_none_or(|commit| r.commit.starts_with(commit)))
+        .map(|r| r.timestamp)
+        .max()
+        .ok_or("No baseline run found for this machine")?;
+
+    let comparisons = current
+        .iter()
+        .filter_map(|current| {
+            let baseline = history.iter().rev().find(|r| {
+                r.timestamp == baseline_timestamp
+                    && r.machine == latest.machine
+                    && r.day == current.day
+                    && r.part == current.part
+            })?;
+            Some(Comparison {
+                day: current.day,
+                part: current.part,
+                baseline: baseline.stats,
+                current: current.stats,
+                t: current.stats.welch_t(&baseline.stats),
+            })
+        })
+        .collect();
+    Ok(comparisons)
+}
+
+fn git_commit() -> String {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output();
+    let Ok(output) = output else {
+        return "unknown".to_string();
+    };
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let dirty = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .is_ok_and(|status| !status.stdout.is_empty());
+    match (commit.is_empty(), dirty) {
+        (true, _) => "unknown".to_string(),
+        (false, true) => format!("{}-dirty", commit),
+        (false, false) => commit,
+    }
+}
+
+fn machine_id() -> String {
+    if let Ok(machine) = std::env::var("AOC_MACHINE") {
+        return machine;
+    }
+    fs::read_to_string("/etc/hostname")
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub fn read_history() -> Vec<Record> {
+    let Ok(content) = fs::read_to_string(HISTORY_PATH) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| Record::from_str(line).expect("Corrupt benchmark history line"))
+        .collect()
+}
+
+fn append_history(records: &[Record]) {
+    fs::create_dir_all("bench").expect("Couldn't create bench directory");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(HISTORY_PATH)
+        .expect("Couldn't open benchmark history");
+    for record in records {
+        writeln!(file, "{}", record).expect("Couldn't write benchmark history");
+    }
+}
+
+/// Times one part `runs` times after a warm-up run. Returns `None` if the part panics,
+/// e.g. because it is still `todo!()`.
+fn time_part(solve: impl Fn() -> String, runs: usize) -> Option<Stats> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        solve();
+        let samples: Vec<f64> = (0..runs)
+            .map(|_| {
+                let time = Instant::now();
+                std::hint::black_box(solve());
+                time.elapsed().as_nanos() as f64
+            })
+            .collect();
+        Stats::from_samples(&samples)
+    }))
+    .ok()
+}
+
+/// `bench <day|all> [--runs N]`
+pub fn bench_command(args: &[String]) {
+    let target = args
+        .first()
+        .expect("Please provide a day or `all` to benchmark");
+    let runs = match args.iter().position(|arg| arg == "--runs") {
+        Some(idx) => args
+            .get(idx + 1)
+            .and_then(|runs| runs.parse().ok())
+            .expect("--runs needs a number"),
+        None => DEFAULT_RUNS,
+    };
+    let days: Vec<u8> = match target.as_str() {
+        "all" => (1..=crate::DAY_COUNT)
+            .filter(|day| crate::read_input(*day).is_some())
+            .collect(),
+        day => vec![u8::from_str(day).expect("Unparseable day given")],
+    };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System clock before 1970")
+        .as_millis() as u64;
+    let commit = git_commit();
+    let machine = machine_id();
+    let mut records = vec![];
+
+    for day_number in days {
+        let Some(day) = crate::get_day(day_number) else {
+            println!("Day {:02}: not implemented, skipped", day_number);
+            continue;
+        };
+        let input = crate::read_input(day_number).expect("Couldn't read file");
+        let parts: [(u8, Box<dyn Fn() -> String>); 2] = [
+            (1, Box::new(|| day.solve_part_one(&input))),
+            (2, Box::new(|| day.solve_part_two(&input))),
+        ];
+        for (part, solve) in parts {
+            let Some(stats) = time_part(solve, runs) else {
+                println!("Day {:02} part {}: failed, skipped", day_number, part);
+                continue;
+            };
+            println!(
+                "Day {:02} part {}: {:.4} ms ± {:.4} ms (median {:.4} ms, {} runs)",
+                day_number,
+                part,
+                stats.mean_ns / 1e6,
+                stats.stddev_ns / 1e6,
+                stats.median_ns / 1e6,
+                stats.runs
+            );
+            records.push(Record {
+                timestamp,
+                commit: commit.clone(),
+                machine: machine.clone(),
+                day: day_number,
+                part,
+                stats,
+            });
+        }
+    }
+
+    append_history(&records);
+    println!("Appended {} results to {}", records.len(), HISTORY_PATH);
+}
+
+/// `bench-diff [baseline commit]`
+pub fn diff_command(args: &[String]) {
+    let history = read_history();
+    let comparisons =
+        compare(&history, args.first().map(String::as_str)).unwrap_or_else(|err| panic!("{}", err));
+
+    let mut regressions = 0;
+    for comparison in &comparisons {
+        let flag = if comparison.is_regression() {
+            regressions += 1;
+            "  REGRESSION"
+        } else {
+            ""
+        };
+        println!(
+            "Day {:02} part {}: {:.4} ms -> {:.4} ms ({:+.1}%, t = {:.2}){}",
+            comparison.day,
+            comparison.part,
+            comparison.baseline.mean_ns / 1e6,
+            comparison.current.mean_ns / 1e6,
+            comparison.relative_change() * 100.0,
+            comparison.t,
+            flag
+        );
+    }
+    println!("{} significant slowdown(s)", regressions);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(timestamp: u64, commit: &str, day: u8, mean_ns: f64, stddev_ns: f64) -> Record {
+        Record {
+            timestamp,
+            commit: commit.to_string(),
+            machine: "box".to_string(),
+            day,
+            part: 1,
+            stats: Stats {
+                runs: 10,
+                mean_ns,
+                stddev_ns,
+                min_ns: mean_ns,
+                median_ns: mean_ns,
+                max_ns: mean_ns,
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let stats = Stats::from_samples(&[4.0, 2.0, 6.0, 8.0]);
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.mean_ns, 5.0);
+        assert_eq!(stats.median_ns, 5.0);
+        assert_eq!(stats.min_ns, 2.0);
+        assert_eq!(stats.max_ns, 8.0);
+        assert!((stats.stddev_ns - (20.0_f64 / 3.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_record_roundtrip() {
+        let known = record(1700000000, "abc1234", 8, 1500.0, 20.0);
+        assert_eq!(Record::from_str(&known.to_string()), Ok(known));
+    }
+
+    #[test]
+    fn test_compare_flags_significant_slowdown() {
+        let history = [
+            record(1, "aaaaaaa", 1, 1000.0, 10.0),
+            record(1, "aaaaaaa", 2, 1000.0, 10.0),
+            record(2, "bbbbbbb", 1, 1500.0, 10.0),
+            record(2, "bbbbbbb", 2, 1002.0, 10.0),
+        ];
+        let comparisons = compare(&history, None).unwrap();
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons[0].is_regression());
+        assert!(!comparisons[1].is_regression());
+    }
+
+    #[test]
+    fn test_compare_against_named_baseline() {
+        let history = [
+            record(1, "aaaaaaa", 1, 1000.0, 10.0),
+            record(2, "bbbbbbb", 1, 2000.0, 10.0),
+            record(3, "ccccccc", 1, 2000.0, 10.0),
+        ];
+        let comparisons = compare(&history, Some("aaa")).unwrap();
+        assert_eq!(comparisons[0].baseline.mean_ns, 1000.0);
+        assert!(comparisons[0].is_regression());
+    }
+}
@@ -1,4 +1,48 @@
+use std::fmt::Display;
+
+use crate::trace::Tracer;
+
 pub trait Day {
     fn solve_part_one(&self, input: &str) -> String;
     fn solve_part_two(&self, input: &str) -> String;
+
+    /// Solves part one while emitting trace events. Days without tracing ignore the tracer.
+    fn trace_part_one(&self, input: &str, _tracer: &mut Tracer) -> String {
+        self.solve_part_one(input)
+    }
+
+    /// Solves part two while emitting trace events. Days without tracing ignore the tracer.
+    fn trace_part_two(&self, input: &str, _tracer: &mut Tracer) -> String {
+        self.solve_part_two(input)
+    }
+
+    /// Solves part one together with a witness and checks the witness against the input
+    /// independently of the solver. `None` if the day provides no witness for this part.
+    fn verify_part_one(&self, _input: &str) -> Option<Verification> {
+        None
+    }
+
+    /// Solves part two together with a witness and checks the witness against the input
+    /// independently of the solver. `None` if the day provides no witness for this part.
+    fn verify_part_two(&self, _input: &str) -> Option<Verification> {
+        None
+    }
+}
+
+/// An answer, the witness backing it and whether the witness holds up against the input.
+#[derive(Debug, PartialEq)]
+pub struct Verification {
+    pub answer: String,
+    pub witness: String,
+    pub outcome: Result<(), String>,
+}
+
+impl Verification {
+    pub fn new(answer: impl Display, witness: impl Display, outcome: Result<(), String>) -> Self {
+        Verification {
+            answer: answer.to_string(),
+            witness: witness.to_string(),
+            outcome,
+        }
+    }
 }
@@ -1,103 +1,126 @@
 use crate::day::Day;
+use crate::trace::Tracer;
+use crate::utils::parsing::{self, Field, ParseResult, or_panic};
 pub struct Day01;
 
-#[derive(PartialEq, Eq, Debug)]
-enum Op {
-    Add,
-    Sub,
-}
+const DIAL_SIZE: u64 = 100;
+const DIAL_START: u64 = 50;
+
 impl Day for Day01 {
     fn solve_part_one(&self, input: &str) -> String {
-        let instructions = input.lines().map(|line| self.parse_instruction(line));
-        let mut current_value = 50;
-        let mut counter = 0;
-        for instruction in instructions {
-            self.apply_instruction(&mut current_value, &mut counter, instruction);
+        self.trace_part_one(input, &mut Tracer::disabled())
+    }
+
+    fn solve_part_two(&self, input: &str) -> String {
+        self.trace_part_two(input, &mut Tracer::disabled())
+    }
+
+    fn trace_part_one(&self, input: &str, tracer: &mut Tracer) -> String {
+        let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+        let mut counter: u64 = 0;
+        for (step, rotation) in self.parse_input(input).into_iter().enumerate() {
+            dial.turn(rotation);
+            if dial.position() == 0 {
+                counter += 1;
+            }
+            trace_turn(tracer, step, rotation, &dial, counter);
         }
 
         format!("{}", counter)
     }
 
-    fn solve_part_two(&self, input: &str) -> String {
-        let instructions = input.lines().map(|line| self.parse_instruction(line));
-        let mut current_value = 50;
-        let mut counter = 0;
-        for instruction in instructions {
-            self.apply_instruction_part_two(&mut current_value, &mut counter, &instruction);
+    fn trace_part_two(&self, input: &str, tracer: &mut Tracer) -> String {
+        let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+        let mut counter: u64 = 0;
+        for (step, rotation) in self.parse_input(input).into_iter().enumerate() {
+            counter += dial.turn(rotation);
+            trace_turn(tracer, step, rotation, &dial, counter);
         }
 
         format!("{}", counter)
     }
 }
 
-impl Day01 {
-    fn parse_instruction(&self, instruction: &str) -> (Op, u16) {
-        let mut chars = instruction.chars();
-        let operator = chars.next().unwrap();
-
-        let op = match operator {
-            'L' => Op::Sub,
-            'R' => Op::Add,
-            _ => unimplemented!(),
-        };
-        let value = chars
-            .collect::<String>()
-            .parse::<u16>()
-            .expect("Could not parse remaining string to digit");
-        (op, value)
-    }
+fn trace_turn(tracer: &mut Tracer, step: usize, rotation: i64, dial: &Dial, zeros: u64) {
+    tracer.emit("dial", || {
+        vec![
+            ("step", step.into()),
+            ("rotation", rotation.into()),
+            ("position", dial.position().into()),
+            ("zeros", zeros.into()),
+        ]
+    });
+}
 
-    fn apply_instruction(&self, current_value: &mut u8, counter: &mut i32, instruction: (Op, u16)) {
-        let (op, value) = instruction;
-        let value = (value % 100) as u8;
-        *current_value = match op {
-            Op::Add => (*current_value + value) % 100,
-            Op::Sub => (*current_value + 100 - value) % 100,
-        };
+/// A dial numbered `0..size` that turns one click per unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Dial {
+    size: u64,
+    position: u64,
+}
 
-        if *current_value == 0 {
-            *counter += 1;
+impl Dial {
+    fn new(size: u64, start: u64) -> Self {
+        assert!(size > 0, "A dial needs at least one position");
+        assert!(
+            start < size,
+            "Start {} is not on a dial of size {}",
+            start,
+            size
+        );
+        Dial {
+            size,
+            position: start,
         }
     }
 
-    fn apply_instruction_part_two(
-        &self,
-        current_value: &mut u8,
-        counter: &mut u16,
-        instruction: &(Op, u16),
-    ) {
-        let (op, value) = instruction;
-
-        let quot = value / 100;
-        let value = (value % 100) as i32;
-
-        *counter += quot;
+    fn position(&self) -> u64 {
+        self.position
+    }
 
-        let tmp: i32 = match op {
-            Op::Add => *current_value as i32 + value,
-            Op::Sub => *current_value as i32 - value,
+    /// Turns the dial right for positive and left for negative `rotation` and returns how
+    /// many clicks pointed it at zero, including the one it stops on.
+    fn turn(&mut self, rotation: i64) -> u64 {
+        let distance = rotation.unsigned_abs();
+        // Clicks needed to reach zero from here in the direction of the turn
+        let to_zero = if rotation >= 0 {
+            self.size - self.position
+        } else if self.position == 0 {
+            self.size
+        } else {
+            self.position
+        };
+        let zeros = if distance >= to_zero {
+            (distance - to_zero) / self.size + 1
+        } else {
+            0
         };
+        let step = distance % self.size;
+        self.position = if rotation >= 0 {
+            (self.position + step) % self.size
+        } else {
+            (self.position + self.size - step) % self.size
+        };
+        zeros
+    }
+}
 
-        match tmp {
-            100.. => {
-                *current_value = (tmp % 100) as u8;
-                *counter += 1;
-            }
-            ..1 => {
-                if *current_value != 0 {
-                    *counter += 1;
-                }
-                *current_value = ((tmp + 100) % 100) as u8;
-            }
-            _ => *current_value = (tmp % 100) as u8,
-        }
+impl Day01 {
+    fn parse_input(&self, input: &str) -> Vec<i64> {
+        let rotations = parsing::non_empty_lines(input)
+            .map(|line| self.parse_rotation(line))
+            .collect();
+        or_panic(rotations)
+    }
+
+    /// Parses `L68` as -68 and `R48` as 48.
+    fn parse_rotation(&self, line: Field) -> ParseResult<i64> {
+        line.signed(('L', 'R'))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::iter::zip;
-
     use super::*;
     const EXAMPLE_INPUT: &str = "L68
 L30
@@ -112,145 +135,110 @@ L82";
 
     #[test]
     fn test_simple_instruction_parsing() {
-        assert_eq!((Op::Sub, 1), Day01.parse_instruction("L1"));
-    }
-
-    #[test]
-    fn test_apply_instruction_addition() {
-        let mut counter = 0;
-        let mut value = 5;
-
-        Day01.apply_instruction(&mut value, &mut counter, (Op::Add, 4));
-
-        assert_eq!(value, 9);
+        assert_eq!(Ok(-1), Day01.parse_rotation(Field::new("L1")));
+        assert_eq!(Ok(48), Day01.parse_rotation(Field::new("R48")));
     }
 
     #[test]
-    fn test_apply_instruction_addition_across_zero() {
-        let mut counter = 0;
-        let mut value = 5;
-
-        Day01.apply_instruction(&mut value, &mut counter, (Op::Add, 99));
-
-        assert_eq!(value, 4);
-    }
-
-    #[test]
-    fn test_apply_instruction_addition_onto_zero() {
-        let mut counter = 0;
-        let mut value = 5;
-
-        Day01.apply_instruction(&mut value, &mut counter, (Op::Add, 95));
-
-        assert_eq!(counter, 1);
+    fn test_parsing_errors() {
+        let error = Day01.parse_rotation(Field::new("X12")).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "X12"));
+        assert!(Day01.parse_rotation(Field::new("L1x")).is_err());
+        assert!(Day01.parse_rotation(Field::new("R")).is_err());
+        assert!(Day01.parse_rotation(Field::new("L-5")).is_err());
+        assert!(Day01.parse_rotation(Field::new("L+5")).is_err());
+        assert!(Day01.parse_rotation(Field::new("L 5")).is_err());
     }
 
     #[test]
-    fn test_apply_instruction_subtraction() {
-        let mut counter = 0;
-        let mut value = 5;
-
-        Day01.apply_instruction(&mut value, &mut counter, (Op::Sub, 4));
-
-        assert_eq!(value, 1);
+    fn test_turn_right() {
+        let mut dial = Dial::new(100, 5);
+        assert_eq!(dial.turn(4), 0);
+        assert_eq!(dial.position(), 9);
+        // Across zero
+        assert_eq!(dial.turn(99), 1);
+        assert_eq!(dial.position(), 8);
+        // Onto zero
+        assert_eq!(dial.turn(92), 1);
+        assert_eq!(dial.position(), 0);
     }
 
     #[test]
-    fn test_apply_instruction_subtraction_across_zero() {
-        let mut counter = 0;
-        let mut value = 5;
-
-        Day01.apply_instruction(&mut value, &mut counter, (Op::Sub, 6));
-
-        assert_eq!(value, 99);
+    fn test_turn_left() {
+        let mut dial = Dial::new(100, 5);
+        assert_eq!(dial.turn(-4), 0);
+        assert_eq!(dial.position(), 1);
+        // Onto zero
+        assert_eq!(dial.turn(-1), 1);
+        assert_eq!(dial.position(), 0);
+        // Leaving zero does not count it again
+        assert_eq!(dial.turn(-1), 0);
+        assert_eq!(dial.position(), 99);
+        // Across zero
+        assert_eq!(dial.turn(-100), 1);
+        assert_eq!(dial.position(), 99);
     }
 
     #[test]
-    fn test_apply_instruction_subtraction_onto_zero() {
-        let mut counter = 0;
-        let mut value = 5;
-
-        Day01.apply_instruction(&mut value, &mut counter, (Op::Sub, 5));
-
-        assert_eq!(counter, 1);
+    fn test_full_turns_and_other_sizes() {
+        let mut dial = Dial::new(100, 50);
+        assert_eq!(dial.turn(1000), 10);
+        assert_eq!(dial.turn(-250), 3);
+        assert_eq!(dial.position(), 0);
+        assert_eq!(dial.turn(i64::MIN), i64::MIN.unsigned_abs() / 100);
+
+        let mut dial = Dial::new(7, 3);
+        assert_eq!(dial.turn(-10), 2);
+        assert_eq!(dial.position(), 0);
+        assert_eq!(dial.turn(7), 1);
+        assert_eq!(dial.position(), 0);
     }
 
     #[test]
     fn test_example_input_parsing() {
-        let instructions = EXAMPLE_INPUT
-            .lines()
-            .map(|line| Day01.parse_instruction(line))
-            .collect::<Vec<(Op, u16)>>();
         assert_eq!(
-            instructions,
-            vec![
-                (Op::Sub, 68),
-                (Op::Sub, 30),
-                (Op::Add, 48),
-                (Op::Sub, 5),
-                (Op::Add, 60),
-                (Op::Sub, 55),
-                (Op::Sub, 1),
-                (Op::Sub, 99),
-                (Op::Add, 14),
-                (Op::Sub, 82),
-            ]
+            Day01.parse_input(EXAMPLE_INPUT),
+            vec![-68, -30, 48, -5, 60, -55, -1, -99, 14, -82]
         );
     }
 
     #[test]
     fn test_example_input_running_part1() {
-        let instructions = [
-            (Op::Sub, 68),
-            (Op::Sub, 30),
-            (Op::Add, 48),
-            (Op::Sub, 5),
-            (Op::Add, 60),
-            (Op::Sub, 55),
-            (Op::Sub, 1),
-            (Op::Sub, 99),
-            (Op::Add, 14),
-            (Op::Sub, 82),
-        ];
-        let mut value = 50;
-        let mut counter = 0;
-        let expected_values = [82, 52, 0, 95, 55, 0, 99, 0, 14, 32];
-
-        for (instruction, expected_value) in zip(instructions, expected_values) {
-            Day01.apply_instruction(&mut value, &mut counter, instruction);
-            assert_eq!(value, expected_value);
-        }
-
-        assert_eq!(3, counter);
+        let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+        let positions: Vec<u64> = Day01
+            .parse_input(EXAMPLE_INPUT)
+            .into_iter()
+            .map(|rotation| {
+                dial.turn(rotation);
+                dial.position()
+            })
+            .collect();
+        assert_eq!(positions, [82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+        assert_eq!("3", Day01.solve_part_one(EXAMPLE_INPUT));
     }
 
     #[test]
     fn test_example_input_running_part2() {
-        let instructions = [
-            (Op::Sub, 68),
-            (Op::Sub, 30),
-            (Op::Add, 48),
-            (Op::Sub, 5),
-            (Op::Add, 60),
-            (Op::Sub, 55),
-            (Op::Sub, 1),
-            (Op::Sub, 99),
-            (Op::Add, 14),
-            (Op::Sub, 82),
-        ];
-        let mut value = 50;
-        let mut counter = 0;
-        let expected_values = [82, 52, 0, 95, 55, 0, 99, 0, 14, 32];
-
-        for (instruction, expected_value) in zip(instructions, expected_values) {
-            Day01.apply_instruction_part_two(&mut value, &mut counter, &instruction);
-            println!(
-                "Instruction: {:?} - value {} , counter {}",
-                instruction, value, counter
-            );
-            assert_eq!(value, expected_value);
-        }
+        let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+        let zeros: Vec<u64> = Day01
+            .parse_input(EXAMPLE_INPUT)
+            .into_iter()
+            .map(|rotation| dial.turn(rotation))
+            .collect();
+        assert_eq!(zeros, [1, 0, 1, 0, 1, 1, 0, 1, 0, 1]);
+        assert_eq!("6", Day01.solve_part_two(EXAMPLE_INPUT));
+    }
 
-        assert_eq!(6, counter);
+    #[test]
+    fn test_example_input_trace_part2() {
+        let mut buffer = vec![];
+        let mut tracer = Tracer::new(&mut buffer, 1);
+        assert_eq!("6", Day01.trace_part_two(EXAMPLE_INPUT, &mut tracer));
+        drop(tracer);
+
+        let events = String::from_utf8(buffer).unwrap();
+        assert_eq!(events.lines().count(), 10);
+        assert!(events.lines().next().unwrap().contains("\"position\":82"));
+        assert!(events.lines().last().unwrap().contains("\"zeros\":6"));
     }
 }
@@ -1,84 +1,90 @@
-use crate::day::Day;
-use std::ops::Range;
+use crate::day::{Day, Verification};
+use crate::utils::interval_set::IntervalSet;
+use crate::utils::parsing::{self, or_panic};
+use crate::utils::repetition::RepetitionRule;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
 pub struct Day02;
 
+/// Part one only counts patterns repeated twice, part two any number of times.
+const TWICE: RepetitionRule = RepetitionRule::exactly(2);
+const AT_LEAST_TWICE: RepetitionRule = RepetitionRule::at_least(2);
+
 impl Day for Day02 {
     fn solve_part_one(&self, input: &str) -> String {
-        let mut invalids: Vec<u64> = vec![];
-        let ranges = input
-            .split(",")
-            .map(|range_| self.parse_instruction(range_.trim()));
-        for range_ in ranges {
-            for number in range_ {
-                if is_invalid_number(number) {
-                    invalids.push(number);
-                }
-            }
-        }
-        invalids.iter().sum::<u64>().to_string()
+        self.invalid_sum(input, TWICE).to_string()
     }
 
     fn solve_part_two(&self, input: &str) -> String {
-        let mut invalids: Vec<u64> = vec![];
-        let ranges = input
-            .split(",")
-            .map(|range_| self.parse_instruction(range_.trim()));
-        for range_ in ranges {
-            for number in range_ {
-                if is_invalid_number_part_two(number) {
-                    invalids.push(number);
-                }
-            }
-        }
-        invalids.iter().sum::<u64>().to_string()
+        self.invalid_sum(input, AT_LEAST_TWICE).to_string()
     }
-}
 
-fn is_invalid_number(number: u64) -> bool {
-    let string = number.to_string();
-    let string = string.as_bytes();
-    let n = string.len();
-    if n % 2 != 0 {
-        return false;
+    fn verify_part_one(&self, input: &str) -> Option<Verification> {
+        Some(self.verify(input, TWICE))
     }
-    let mut idx1 = 0;
-    let mut idx2 = n / 2;
-    while idx2 < n && string[idx1] == string[idx2] {
-        idx1 += 1;
-        idx2 += 1;
+
+    fn verify_part_two(&self, input: &str) -> Option<Verification> {
+        Some(self.verify(input, AT_LEAST_TWICE))
     }
-    idx2 >= n
 }
 
-fn is_invalid_number_part_two(number: u64) -> bool {
-    let string = number.to_string();
-    let string = string.as_bytes();
-    let n = string.len();
-    let max_pattern_size = n / 2 + 1;
-
-    for i in 1..max_pattern_size {
-        if n % i != 0 {
-            continue;
+/// Checks that every witnessed ID lies in one of the ranges, is a repeated pattern, is listed
+/// once and that the IDs add up to `answer`. Does not prove that no invalid ID was missed.
+fn check_invalids(
+    ids: &IntervalSet<u64>,
+    invalids: &[u64],
+    rule: RepetitionRule,
+    answer: u128,
+) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for &id in invalids {
+        if !ids.contains(id) {
+            return Err(format!("{} is not in any range", id));
         }
-        let pattern = &string[..i];
-
-        if pattern.repeat(n / i).as_slice() == string {
-            return true;
+        if !seen.insert(id) {
+            return Err(format!("{} is listed twice", id));
+        }
+        if rule.matched_period(id).is_none() {
+            return Err(format!("{} is not a repeated pattern", id));
         }
     }
-
-    false
+    let total = invalids.iter().map(|id| *id as u128).sum::<u128>();
+    if total != answer {
+        return Err(format!("Invalid IDs sum to {}, not {}", total, answer));
+    }
+    Ok(())
 }
 
 impl Day02 {
-    fn parse_instruction(&self, instruction: &str) -> Range<u64> {
-        let mut ends = instruction
-            .split("-")
-            .map(|x| x.parse::<u64>().expect("Could not parse number for range"));
-        let start = ends.next().unwrap();
-        let end = ends.next().unwrap();
+    fn invalid_sum(&self, input: &str, rule: RepetitionRule) -> u128 {
+        self.parse_ids(input)
+            .iter()
+            .map(|range_| rule.sum_in(&range_))
+            .sum()
+    }
+
+    fn verify(&self, input: &str, rule: RepetitionRule) -> Verification {
+        let ids = self.parse_ids(input);
+        let invalids: Vec<u64> = ids
+            .iter()
+            .flat_map(|range_| rule.matches_in(&range_))
+            .collect();
+        let answer = self.invalid_sum(input, rule);
+        let outcome = check_invalids(&ids, &invalids, rule, answer);
+        Verification::new(answer, format!("{:?}", invalids), outcome)
+    }
 
-        start..end + 1
+    /// The IDs in any of the ranges, with overlaps merged so that every ID counts once.
+    fn parse_ids(&self, input: &str) -> IntervalSet<u64> {
+        self.parse_input(input).into_iter().collect()
+    }
+
+    fn parse_input(&self, input: &str) -> Vec<RangeInclusive<u64>> {
+        let ranges = parsing::non_empty_lines(input)
+            .flat_map(|line| line.split(','))
+            .map(|range_| range_.range())
+            .collect();
+        or_panic(ranges)
     }
 }
 
@@ -92,36 +98,33 @@ mod test {
 
     #[test]
     fn test_simple_instruction_parsing() {
-        assert_eq!(11..23, Day02.parse_instruction("11-22"));
+        assert_eq!(Day02.parse_input("11-22"), vec![11..=22]);
     }
 
     #[test]
     fn test_simple_invalid_checking() {
         for invalid in KNOWN_INVALIDS {
-            assert!(is_invalid_number(invalid))
+            assert!(TWICE.matched_period(invalid).is_some())
         }
     }
 
     #[test]
     fn test_example_input_parsing() {
-        let ranges: Vec<Range<u64>> = EXAMPLE_INPUT
-            .split(",")
-            .map(|range_| Day02.parse_instruction(range_))
-            .collect();
+        let ranges = Day02.parse_input(EXAMPLE_INPUT);
         assert_eq!(
             ranges,
             [
-                11..23,
-                95..116,
-                998..1013,
-                1188511880..1188511891,
-                222220..222225,
-                1698522..1698529,
-                446443..446450,
-                38593856..38593863,
-                565653..565660,
-                824824821..824824828,
-                2121212118..2121212125
+                11..=22,
+                95..=115,
+                998..=1012,
+                1188511880..=1188511890,
+                222220..=222224,
+                1698522..=1698528,
+                446443..=446449,
+                38593856..=38593862,
+                565653..=565659,
+                824824821..=824824827,
+                2121212118..=2121212124
             ]
         );
     }
@@ -142,7 +145,51 @@ mod test {
             .into_iter()
             .chain(KNOWN_INVALIDS_REPEATED_PATTERN)
         {
-            assert!(is_invalid_number_part_two(invalid))
+            assert!(AT_LEAST_TWICE.matched_period(invalid).is_some())
         }
     }
+
+    #[test]
+    fn test_example_input_verification() {
+        let verification = Day02.verify_part_one(EXAMPLE_INPUT).unwrap();
+        assert_eq!(verification.answer, "1227775554");
+        assert_eq!(verification.outcome, Ok(()));
+        assert_eq!(
+            Day02.verify_part_two(EXAMPLE_INPUT).unwrap().outcome,
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        for input in ["11-22,11-22", "11-15,12-22", "11-16,17-22"] {
+            assert_eq!(Day02.solve_part_one(input), "33");
+            let verification = Day02.verify_part_one(input).unwrap();
+            assert_eq!(verification.answer, "33");
+            assert_eq!(verification.witness, "[11, 22]");
+            assert_eq!(verification.outcome, Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_range_up_to_u64_max() {
+        let input = "1-18446744073709551615";
+        assert_eq!(
+            Day02.solve_part_one(input),
+            TWICE.sum_in(&(1..=u64::MAX)).to_string()
+        );
+        let verification = Day02.verify_part_two("18446744073709551515-18446744073709551615");
+        assert_eq!(verification.unwrap().outcome, Ok(()));
+    }
+
+    #[test]
+    fn test_check_invalids_rejects_bad_witness() {
+        let ranges: IntervalSet<u64> = [11..=22, 95..=115].into_iter().collect();
+        assert!(check_invalids(&ranges, &[11, 22, 99], TWICE, 132).is_ok());
+        assert!(check_invalids(&ranges, &[11, 33], TWICE, 44).is_err());
+        assert!(check_invalids(&ranges, &[11, 11], TWICE, 22).is_err());
+        assert!(check_invalids(&ranges, &[12], AT_LEAST_TWICE, 12).is_err());
+        assert!(check_invalids(&ranges, &[111], AT_LEAST_TWICE, 111).is_ok());
+        assert!(check_invalids(&ranges, &[111], TWICE, 111).is_err());
+    }
 }
@@ -1,63 +1,171 @@
-use crate::day::Day;
+use crate::day::{Day, Verification};
+use crate::utils::parsing::{self, or_panic};
 pub struct Day03;
 
 impl Day for Day03 {
     fn solve_part_one(&self, input: &str) -> String {
-        let banks = self.parse_instruction(input);
-        let mut joltages: Vec<u64> = vec![];
-        for bank in banks {
-            joltages.push(find_nth_max_values(&bank, 2));
-        }
-        format!("{}", joltages.iter().sum::<u64>())
+        self.max_joltages(input, 2).0
     }
 
     fn solve_part_two(&self, input: &str) -> String {
-        let banks = self.parse_instruction(input);
-        let mut joltages: Vec<u64> = vec![];
-        for bank in banks {
-            joltages.push(find_nth_max_values(&bank, 12));
-        }
-        format!("{}", joltages.iter().sum::<u64>())
+        self.max_joltages(input, 12).0
+    }
+
+    fn verify_part_one(&self, input: &str) -> Option<Verification> {
+        Some(self.verify(input, 2))
+    }
+
+    fn verify_part_two(&self, input: &str) -> Option<Verification> {
+        Some(self.verify(input, 12))
     }
 }
 
-fn find_nth_max_values(bank: &[u8], digit_count: u8) -> u64 {
-    let mut result: u64 = 0;
-    let n = bank.len();
-    let mut start_idx = 0;
-    for i in (0..digit_count).rev() {
-        let (max, updated_start_idx) = max_with_index(&bank[start_idx..n - i as usize]);
-        start_idx += updated_start_idx + 1;
-        result += max as u64 * 10_u64.pow(i as u32);
+/// Indices of the `digit_count` batteries that spell the largest joltage, in order, or
+/// `None` if the bank is too small.
+///
+/// Keeps a stack of chosen batteries and drops the last one whenever a larger battery comes
+/// along and enough batteries are left to refill the stack, so every battery is pushed and
+/// popped at most once.
+fn choose_batteries(bank: &[u8], digit_count: usize) -> Option<Vec<usize>> {
+    if bank.len() < digit_count {
+        return None;
+    }
+    let mut chosen: Vec<usize> = Vec::with_capacity(digit_count);
+    for (idx, &battery) in bank.iter().enumerate() {
+        let left = bank.len() - idx;
+        while let Some(&last) = chosen.last() {
+            if bank[last] >= battery || chosen.len() - 1 + left < digit_count {
+                break;
+            }
+            chosen.pop();
+        }
+        if chosen.len() < digit_count {
+            chosen.push(idx);
+        }
     }
+    Some(chosen)
+}
 
-    result
+/// The joltage of the chosen batteries as a decimal string, which never overflows.
+fn joltage(bank: &[u8], chosen: &[usize]) -> String {
+    chosen
+        .iter()
+        .map(|&idx| char::from(b'0' + bank[idx]))
+        .collect()
 }
 
-fn max_with_index(iter: &[u8]) -> (u8, usize) {
-    let mut max = 0;
-    let mut max_index = 0;
-    for (i, value) in iter.iter().enumerate() {
-        if *value > max {
-            max = *value;
-            max_index = i;
+/// Sum of two non-negative decimal strings.
+fn add_decimal(a: &str, b: &str) -> String {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut digits = vec![];
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let digit = |number: &[u8]| {
+            number
+                .len()
+                .checked_sub(i + 1)
+                .map_or(0, |idx| number[idx] - b'0')
+        };
+        let sum = digit(a) + digit(b) + carry;
+        digits.push(b'0' + sum % 10);
+        carry = sum / 10;
+    }
+    if carry > 0 {
+        digits.push(b'0' + carry);
+    }
+    while digits.len() > 1 && digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|&digit| char::from(digit))
+        .collect()
+}
+
+/// Checks that the chosen batteries of every bank are in order, form the largest possible
+/// joltage and add up to `answer`.
+///
+/// A choice is maximal iff every chosen digit is the largest digit available between the
+/// previous choice and the last position that still leaves room for the remaining digits.
+fn check_chosen_batteries(
+    banks: &[Vec<u8>],
+    chosen: &[Vec<usize>],
+    digit_count: usize,
+    answer: &str,
+) -> Result<(), String> {
+    if banks.len() != chosen.len() {
+        return Err(format!(
+            "{} banks but {} choices",
+            banks.len(),
+            chosen.len()
+        ));
+    }
+    let mut total = "0".to_string();
+    for (bank_idx, (bank, indices)) in banks.iter().zip(chosen).enumerate() {
+        if indices.len() != digit_count {
+            return Err(format!(
+                "Bank {}: {} batteries chosen",
+                bank_idx,
+                indices.len()
+            ));
         }
+        let mut window_start = 0;
+        for (i, &idx) in indices.iter().enumerate() {
+            let window_end = bank.len() + i - digit_count;
+            if idx < window_start || idx > window_end {
+                return Err(format!("Bank {}: battery {} out of order", bank_idx, idx));
+            }
+            let best = bank[window_start..=window_end].iter().max().unwrap();
+            if bank[idx] != *best {
+                return Err(format!(
+                    "Bank {}: battery {} is {}, but {} was available",
+                    bank_idx, idx, bank[idx], best
+                ));
+            }
+            window_start = idx + 1;
+        }
+        total = add_decimal(&total, &joltage(bank, indices));
+    }
+    if total != answer {
+        return Err(format!("Chosen batteries sum to {}, not {}", total, answer));
     }
-    (max, max_index)
+    Ok(())
 }
 
 impl Day03 {
-    fn parse_instruction(&self, input: &str) -> Vec<Vec<u8>> {
-        let mut result = vec![];
-        for line in input.lines() {
-            let joltages = line
-                .chars()
-                .map(|x| x.to_digit(10).unwrap() as u8)
-                .collect();
-            result.push(joltages);
+    /// The total of the largest joltages and the batteries chosen in every bank.
+    fn max_joltages(&self, input: &str, digit_count: usize) -> (String, Vec<Vec<usize>>) {
+        let banks = self.parse_input(input);
+        let mut total = "0".to_string();
+        let mut chosen = vec![];
+        for (bank_idx, bank) in banks.iter().enumerate() {
+            let indices = choose_batteries(bank, digit_count).unwrap_or_else(|| {
+                panic!(
+                    "Bank {} has {} batteries, {} are needed",
+                    bank_idx,
+                    bank.len(),
+                    digit_count
+                )
+            });
+            total = add_decimal(&total, &joltage(bank, &indices));
+            chosen.push(indices);
         }
+        (total, chosen)
+    }
+
+    fn verify(&self, input: &str, digit_count: usize) -> Verification {
+        let banks = self.parse_input(input);
+        let (answer, chosen) = self.max_joltages(input, digit_count);
+        let outcome = check_chosen_batteries(&banks, &chosen, digit_count, &answer);
+        Verification::new(answer, format!("{:?}", chosen), outcome)
+    }
 
-        result
+    fn parse_input(&self, input: &str) -> Vec<Vec<u8>> {
+        let banks = parsing::non_empty_lines(input)
+            .map(|line| line.digits())
+            .collect();
+        or_panic(banks)
     }
 }
 
@@ -77,7 +185,7 @@ mod test {
 
     #[test]
     fn test_example_input_parsing() {
-        let parsed_example_input = Day03.parse_instruction(EXAMPLE_INPUT);
+        let parsed_example_input = Day03.parse_input(EXAMPLE_INPUT);
         for (expected, parsed) in KNOWN_BANKS.iter().zip(parsed_example_input) {
             assert_eq!(expected, parsed.as_slice());
         }
@@ -93,11 +201,69 @@ mod test {
         assert_eq!(Day03.solve_part_two(EXAMPLE_INPUT), "3121910778619");
     }
 
+    #[test]
+    fn test_example_input_verification() {
+        let verification = Day03.verify_part_one(EXAMPLE_INPUT).unwrap();
+        assert_eq!(verification.witness, "[[0, 1], [0, 14], [13, 14], [6, 11]]");
+        assert_eq!(verification.outcome, Ok(()));
+        assert_eq!(
+            Day03.verify_part_two(EXAMPLE_INPUT).unwrap().outcome,
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_chosen_batteries_rejects_smaller_choice() {
+        let banks = [KNOWN_BANKS[0].to_vec()];
+        assert!(check_chosen_batteries(&banks, &[vec![0, 1]], 2, "98").is_ok());
+        assert!(check_chosen_batteries(&banks, &[vec![0, 2]], 2, "97").is_err());
+        assert!(check_chosen_batteries(&banks, &[vec![1, 0]], 2, "89").is_err());
+    }
+
     #[test]
     fn test_example_input_12_digit_max() {
-        let known_max_values = [987654321111, 811111111119, 434234234278, 888911112111];
+        let known_max_values = [
+            "987654321111",
+            "811111111119",
+            "434234234278",
+            "888911112111",
+        ];
         for (bank, known_max) in KNOWN_BANKS.iter().zip(known_max_values) {
-            assert_eq!(find_nth_max_values(bank, 12), known_max);
+            let chosen = choose_batteries(bank, 12).unwrap();
+            assert_eq!(joltage(bank, &chosen), known_max);
         }
     }
+
+    #[test]
+    fn test_choose_batteries() {
+        let bank = [3, 9, 1, 9, 2, 5];
+        assert_eq!(choose_batteries(&bank, 2), Some(vec![1, 3]));
+        assert_eq!(choose_batteries(&bank, 3), Some(vec![1, 3, 5]));
+        assert_eq!(choose_batteries(&bank, 6), Some(vec![0, 1, 2, 3, 4, 5]));
+        assert_eq!(choose_batteries(&bank, 0), Some(vec![]));
+        assert_eq!(choose_batteries(&bank, 7), None);
+    }
+
+    #[test]
+    fn test_joltages_beyond_u64() {
+        let bank: Vec<u8> = (0..40).map(|idx| (idx % 10) as u8).collect();
+        let chosen = choose_batteries(&bank, 25).unwrap();
+        assert_eq!(joltage(&bank, &chosen), "9678901234567890123456789");
+        assert_eq!(
+            add_decimal("99999999999999999999", "1"),
+            "100000000000000000000"
+        );
+        assert_eq!(add_decimal("0", "0"), "0");
+        assert_eq!(add_decimal("357", "3121910778619"), "3121910778976");
+    }
+
+    #[test]
+    fn test_parsing_errors() {
+        let error = parsing::lines("12\n3x4")
+            .nth(1)
+            .unwrap()
+            .digits()
+            .unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "x"));
+    }
 }
@@ -1,90 +1,119 @@
 use crate::day::Day;
-use std::collections::{HashMap, HashSet};
+use crate::trace::Tracer;
+use crate::utils::geometry::Point2;
+use crate::utils::neighbourhood::{Neighbourhood, ThresholdRule};
+use crate::utils::parsing::{self, or_panic};
+use std::collections::HashSet;
 pub struct Day04;
 
-const DIRS: [(i32, i32); 8] = [
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
+type Position = Point2<i32>;
 
-type Position = (i32, i32);
+/// Forklifts reach rolls with fewer than four rolls around them.
+const FORKLIFT: ThresholdRule = ThresholdRule::new(Neighbourhood::Moore, 4);
 
 impl Day for Day04 {
     fn solve_part_one(&self, input: &str) -> String {
         let live_cells = self.parse_input(input);
-        let mut cell_counts: HashMap<Position, u8> = HashMap::new();
-
-        for live in &live_cells {
-            for (dx, dy) in &DIRS {
-                *cell_counts.entry((live.0 + dx, live.1 + dy)).or_insert(0) += 1;
-            }
-        }
-
-        let count = &live_cells
+        let cell_counts = FORKLIFT.neighbour_counts(&live_cells);
+        let count = live_cells
             .iter()
-            .filter(|x| *cell_counts.get(x).unwrap_or(&0) < 4)
+            .filter(|cell| FORKLIFT.is_below(&cell_counts, cell))
             .count();
 
         format!("{}", count)
     }
+
     fn solve_part_two(&self, input: &str) -> String {
-        let mut live_cells = self.parse_input(input);
-        let mut removed = 0;
+        self.trace_part_two(input, &mut Tracer::disabled())
+    }
 
-        let mut cell_counts: HashMap<Position, u8> = HashMap::new();
-        for live in &live_cells {
-            for (dx, dy) in &DIRS {
-                *cell_counts.entry((live.0 + dx, live.1 + dy)).or_insert(0) += 1;
-            }
-        }
+    fn trace_part_two(&self, input: &str, tracer: &mut Tracer) -> String {
+        let history = remove_accessible(self.parse_input(input), &FORKLIFT, tracer);
+        tracer.emit("settled", || {
+            vec![
+                ("waves", history.wave_count().into()),
+                ("survivors", history.survivors.len().into()),
+            ]
+        });
+        format!("{}", history.removed_count())
+    }
+}
 
-        loop {
-            let to_be_removed: Vec<Position> = live_cells
-                .iter()
-                .filter(|x| *cell_counts.get(x).unwrap_or(&0) < 4)
-                .cloned()
-                .collect();
+/// The rolls removed by every wave and the ones left standing at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RemovalHistory {
+    /// Rolls removed by each wave, in order, each wave sorted by position
+    waves: Vec<Vec<Position>>,
+    survivors: HashSet<Position>,
+}
 
-            if to_be_removed.is_empty() {
-                break;
-            }
+impl RemovalHistory {
+    fn wave_count(&self) -> usize {
+        self.waves.len()
+    }
+
+    fn removed_count(&self) -> usize {
+        self.waves.iter().map(Vec::len).sum()
+    }
+}
+
+/// Removes accessible rolls in waves until none are left. A wave removes every roll that
+/// is accessible when it starts, and only the neighbours of removed rolls can become
+/// accessible, so only those are examined for the next wave.
+fn remove_accessible(
+    mut live_cells: HashSet<Position>,
+    rule: &ThresholdRule,
+    tracer: &mut Tracer,
+) -> RemovalHistory {
+    let mut cell_counts = rule.neighbour_counts(&live_cells);
+    let mut candidates: HashSet<Position> = live_cells.clone();
+    let mut waves = vec![];
+
+    while !candidates.is_empty() {
+        let mut to_be_removed: Vec<Position> = candidates
+            .drain()
+            .filter(|cell| live_cells.contains(cell) && rule.is_below(&cell_counts, cell))
+            .collect();
+        if to_be_removed.is_empty() {
+            break;
+        }
+        to_be_removed.sort_unstable();
+        tracer.emit("wave", || {
+            vec![
+                ("wave", (waves.len() + 1).into()),
+                ("removed", to_be_removed.clone().into()),
+            ]
+        });
 
-            for cell in &to_be_removed {
-                live_cells.remove(cell);
-                removed += 1;
-
-                for (dx, dy) in &DIRS {
-                    let neighbour = (cell.0 + dx, cell.1 + dy);
-                    if let Some(count) = cell_counts.get_mut(&neighbour) {
-                        *count -= 1;
-                        if *count == 0 {
-                            cell_counts.remove(&neighbour);
-                        }
-                    }
+        for cell in &to_be_removed {
+            live_cells.remove(cell);
+        }
+        for cell in &to_be_removed {
+            for neighbour in rule.neighbours(*cell) {
+                if let Some(count) = cell_counts.get_mut(&neighbour) {
+                    *count -= 1;
+                }
+                if live_cells.contains(&neighbour) {
+                    candidates.insert(neighbour);
                 }
             }
         }
-        format!("{}", removed)
+        waves.push(to_be_removed);
+    }
+
+    RemovalHistory {
+        waves,
+        survivors: live_cells,
     }
 }
 
 impl Day04 {
     fn parse_input(&self, input: &str) -> HashSet<Position> {
-        let mut result = HashSet::new();
-        for (y, line) in input.lines().enumerate() {
-            for (x, value) in line.chars().enumerate() {
-                if value == '@' {
-                    result.insert((x as i32, y as i32));
-                }
-            }
-        }
-        result
+        or_panic(parsing::char_grid(input, ".@"))
+            .find_all('@')
+            .into_iter()
+            .map(|(x, y)| Point2::new(x as i32, y as i32))
+            .collect()
     }
 }
 
@@ -101,10 +130,17 @@ mod test {
 @.@@@.@@@@
 .@@@@@@@@.
 @.@.@@@.@.";
+    const BLOCK_3X3: &str = "@@@
+@@@
+@@@";
+    const BLOCK_4X4: &str = "@@@@
+@@@@
+@@@@
+@@@@";
 
     #[test]
     fn test_example_input_parsing() {
-        let known_cells = HashSet::from([
+        let known_cells: HashSet<Position> = [
             (4, 7),
             (8, 9),
             (9, 4),
@@ -176,7 +212,9 @@ mod test {
             (5, 9),
             (0, 1),
             (8, 0),
-        ]);
+        ]
+        .map(Point2::from)
+        .into();
         assert_eq!(Day04.parse_input(EXAMPLE_INPUT), known_cells);
     }
 
@@ -189,4 +227,61 @@ mod test {
     fn test_example_input_running_part2() {
         assert_eq!(Day04.solve_part_two(EXAMPLE_INPUT), "43");
     }
+
+    #[test]
+    fn test_removal_history() {
+        let cells = Day04.parse_input(EXAMPLE_INPUT);
+        let history = remove_accessible(cells.clone(), &FORKLIFT, &mut Tracer::disabled());
+        let wave_sizes: Vec<usize> = history.waves.iter().map(Vec::len).collect();
+        assert_eq!(wave_sizes, [13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert_eq!(history.wave_count(), 9);
+        assert_eq!(history.removed_count(), 43);
+        assert_eq!(history.survivors.len(), cells.len() - 43);
+        assert!(history.survivors.iter().all(|cell| cells.contains(cell)));
+        assert!(history.waves[0].contains(&Point2::new(2, 0)));
+    }
+
+    #[test]
+    fn test_block_erodes_in_three_waves() {
+        // Every roll of a 3x3 block but the corners has at least 4 neighbours, and once
+        // the corners are gone the edges are down to 3
+        let history = remove_accessible(
+            Day04.parse_input(BLOCK_3X3),
+            &FORKLIFT,
+            &mut Tracer::disabled(),
+        );
+        assert_eq!(history.wave_count(), 3);
+        assert!(history.survivors.is_empty());
+    }
+
+    #[test]
+    fn test_removal_from_empty_floor() {
+        let history = remove_accessible(HashSet::new(), &FORKLIFT, &mut Tracer::disabled());
+        assert_eq!(history.wave_count(), 0);
+        assert!(history.survivors.is_empty());
+    }
+
+    #[test]
+    fn test_removal_with_other_rules() {
+        let tracer = &mut Tracer::disabled();
+        // Orthogonally the corners of a 3x3 block have 2 neighbours and the edges 3
+        let von_neumann = ThresholdRule::new(Neighbourhood::VonNeumann, 3);
+        let history = remove_accessible(Day04.parse_input(BLOCK_3X3), &von_neumann, tracer);
+        assert_eq!(history.waves[0].len(), 4);
+        assert_eq!(history.removed_count(), 9);
+        // Nothing ever has fewer than 8 neighbours on a full torus
+        let history = remove_accessible(
+            Day04.parse_input(BLOCK_4X4),
+            &FORKLIFT.on_torus(4, 4),
+            tracer,
+        );
+        assert_eq!(history.wave_count(), 0);
+        assert_eq!(history.survivors.len(), 16);
+        let history = remove_accessible(
+            Day04.parse_input(BLOCK_4X4),
+            &ThresholdRule::new(Neighbourhood::Moore, 9),
+            tracer,
+        );
+        assert_eq!(history.wave_count(), 1);
+    }
 }
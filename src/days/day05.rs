@@ -1,65 +1,34 @@
-use std::{cmp, ops::Range};
+use std::ops::RangeInclusive;
 
 use crate::day::Day;
+use crate::utils::interval_set::IntervalSet;
+use crate::utils::parsing::{self, ParseResult, or_panic};
 pub struct Day05;
 
 impl Day for Day05 {
     fn solve_part_one(&self, input: &str) -> String {
         let (ranges, ids) = Day05.parse_input(input);
-        let count = ids
-            .iter()
-            .filter(|id| ranges.iter().any(|range| range.contains(id)))
-            .count();
+        let fresh: IntervalSet<u64> = ranges.into_iter().collect();
+        let count = ids.iter().filter(|id| fresh.contains(**id)).count();
         format!("{}", count)
     }
 
     fn solve_part_two(&self, input: &str) -> String {
-        let mut non_overlapping_ranges: Vec<Range<u64>> = vec![];
-        let (mut ranges, _) = Day05.parse_input(input);
-        ranges.sort_unstable_by(|x, y| x.start.cmp(&y.start));
-
-        let mut prev_interval = ranges[0].clone();
-        for current_interval in ranges.iter().skip(1) {
-            if current_interval.start < prev_interval.end {
-                let new_end = cmp::max(current_interval.end, prev_interval.end);
-                prev_interval = prev_interval.start..new_end;
-            } else {
-                non_overlapping_ranges.push(prev_interval);
-                prev_interval = current_interval.clone();
-            }
-        }
-        non_overlapping_ranges.push(prev_interval);
-
-        let count = non_overlapping_ranges
-            .iter()
-            .fold(0, |acc, range| acc + (range.end - range.start));
-        format!("{}", count)
+        let (ranges, _) = Day05.parse_input(input);
+        let fresh: IntervalSet<u64> = ranges.into_iter().collect();
+        format!("{}", fresh.len())
     }
 }
 
 impl Day05 {
-    fn parse_input(&self, input: &str) -> (Vec<Range<u64>>, Vec<u64>) {
-        let mut ranges = vec![];
-        let mut ids = vec![];
-        let (raw_ranges, raw_ids) = input
-            .split_once("\n\n")
-            .expect("No blankline found in input string");
-
-        for mut line in raw_ranges.lines() {
-            line = line.trim();
-            let (start, end) = line.split_once("-").unwrap();
-            let start: u64 = start.parse().unwrap();
-            let end: u64 = end.parse().unwrap();
-            ranges.push(start..end + 1);
-        }
-
-        for mut line in raw_ids.lines() {
-            line = line.trim();
-            let id: u64 = line.parse().unwrap();
-            ids.push(id);
-        }
+    fn parse_input(&self, input: &str) -> (Vec<RangeInclusive<u64>>, Vec<u64>) {
+        let [raw_ranges, raw_ids] = parsing::sections(input)[..] else {
+            panic!("Expected ranges and IDs separated by a blank line");
+        };
+        let ranges: ParseResult<Vec<_>> = raw_ranges.lines().map(|line| line.range()).collect();
+        let ids: ParseResult<Vec<_>> = raw_ids.lines().map(|line| line.int()).collect();
 
-        (ranges, ids)
+        (or_panic(ranges), or_panic(ids))
     }
 }
 
@@ -80,7 +49,7 @@ mod test {
 
     #[test]
     fn test_example_input_parsing() {
-        let known_ranges = [3..6, 10..15, 16..21, 12..19];
+        let known_ranges = [3..=5, 10..=14, 16..=20, 12..=18];
         let known_ids = [1, 5, 8, 11, 17, 32];
         let (parsed_ranges, parsed_ids) = Day05.parse_input(EXAMPLE_INPUT);
         assert_eq!(known_ranges, parsed_ranges.as_slice());
@@ -1,7 +1,10 @@
 use crate::day::Day;
+use crate::utils::parsing::{self, Field, ParseResult, or_panic};
 pub struct Day06;
 
 type Problem = (u64, Op);
+/// The number lines, and the operators with the column each one starts at.
+type Sheet<'a> = (Vec<Field<'a>>, Vec<(usize, Op)>);
 
 #[derive(PartialEq, Debug)]
 enum Op {
@@ -24,75 +27,88 @@ impl Day for Day06 {
 }
 
 impl Day06 {
+    fn parse_sheet<'a>(&self, input: &'a str) -> ParseResult<Sheet<'a>> {
+        let mut lines: Vec<Field> = parsing::non_empty_lines(input).collect();
+        let operator_line = lines
+            .pop()
+            .ok_or_else(|| Field::new(input).error("Expected numbers and operators"))?;
+        let operators = operator_line
+            .split(' ')
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                let operation = match field.text {
+                    "+" => Op::Add,
+                    "*" => Op::Mul,
+                    _ => return Err(field.error("Expected `+` or `*`")),
+                };
+                Ok((field.column - operator_line.column, operation))
+            })
+            .collect::<ParseResult<_>>()?;
+        Ok((lines, operators))
+    }
+
     fn parse_input(&self, input: &str) -> Vec<Problem> {
-        let mut result = vec![];
+        or_panic(self.parse_rows(input))
+    }
 
-        let mut lines = input.lines().rev();
-
-        let operator_line = lines.next().unwrap().trim();
-        for char in operator_line.chars() {
-            if !char.is_whitespace() {
-                match char {
-                    '+' => result.push((0, Op::Add)),
-                    '*' => result.push((1, Op::Mul)),
-                    _ => panic!("Unexpected operation character"),
-                }
-            }
-        }
+    /// Reads the numbers along the rows, one per problem on every line.
+    fn parse_rows(&self, input: &str) -> ParseResult<Vec<Problem>> {
+        let (lines, operators) = self.parse_sheet(input)?;
+        let mut result: Vec<Problem> = operators
+            .into_iter()
+            .map(|(_, operation)| match operation {
+                Op::Add => (0, operation),
+                Op::Mul => (1, operation),
+            })
+            .collect();
 
         for line in lines {
-            let mut active_number: u64 = 0;
-            let mut current_column = 0;
-            for char in line.trim().chars() {
-                if char.is_ascii_digit() {
-                    active_number = active_number * 10 + char.to_digit(10).unwrap() as u64
-                } else if active_number != 0 {
-                    let (acc, operation) = result.get_mut(current_column).unwrap();
-                    match operation {
-                        Op::Add => *acc += active_number,
-                        Op::Mul => *acc *= active_number,
-                    }
-                    active_number = 0;
-                    current_column += 1;
-                }
+            let numbers: Vec<u64> = line
+                .split(' ')
+                .filter(|field| !field.is_empty())
+                .map(|field| field.int())
+                .collect::<ParseResult<_>>()?;
+            if numbers.len() != result.len() {
+                return Err(line.error(format!(
+                    "Expected {} numbers, found {}",
+                    result.len(),
+                    numbers.len()
+                )));
             }
-            let (acc, operation) = result.get_mut(current_column).unwrap();
-            match operation {
-                Op::Add => *acc += active_number,
-                Op::Mul => *acc *= active_number,
+            for ((acc, operation), number) in result.iter_mut().zip(numbers) {
+                match operation {
+                    Op::Add => *acc += number,
+                    Op::Mul => *acc *= number,
+                }
             }
         }
-        result
+        Ok(result)
     }
 
     fn parse_input_part_two(&self, input: &str) -> Vec<u64> {
-        let mut result = vec![];
+        or_panic(self.parse_columns(input))
+    }
 
-        let mut lines = input.lines().rev();
-        let mut operators = vec![];
+    /// Reads the numbers down the columns, top digit first.
+    fn parse_columns(&self, input: &str) -> ParseResult<Vec<u64>> {
+        let mut result = vec![];
 
         //IMPORTANT: All operators are always left-aligned in their columns
-        let operator_line = lines.next().unwrap().chars();
-        let mut line_length = 0;
-        for (idx, char) in operator_line.enumerate() {
-            if !char.is_whitespace() {
-                match char {
-                    '+' => operators.push((idx, Op::Add)),
-                    '*' => operators.push((idx, Op::Mul)),
-                    _ => panic!("Unexpected operation character"),
-                }
-            }
-            line_length += 1;
-        }
-        line_length += 1;
+        let (lines, operators) = self.parse_sheet(input)?;
+        let line_length = parsing::non_empty_lines(input)
+            .map(|line| line.text.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 1;
 
         let mut numbers: Vec<u64> = vec![0; line_length];
 
-        for line in lines.rev() {
-            for (idx, char) in line.chars().enumerate() {
-                let active_number = numbers.get_mut(idx).unwrap();
-                if char.is_ascii_digit() {
-                    *active_number = *active_number * 10 + char.to_digit(10).unwrap() as u64
+        for line in lines {
+            for field in line.split(' ').filter(|field| !field.is_empty()) {
+                let start = field.column - line.column;
+                for (offset, digit) in field.digits()?.into_iter().enumerate() {
+                    let active_number = &mut numbers[start + offset];
+                    *active_number = *active_number * 10 + digit as u64;
                 }
             }
         }
@@ -121,7 +137,7 @@ impl Day06 {
                 }
             }
         }
-        result
+        Ok(result)
     }
 }
 
@@ -147,6 +163,19 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_parsing_errors() {
+        let error = Day06.parse_rows("1 2\n3 4\n+ -").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (3, 3, "-"));
+        let error = Day06.parse_rows("1 2\n3\n+ *").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = Day06.parse_rows("1 x\n+ *").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 3, "x"));
+        let error = Day06.parse_columns("12 3\n4x 5\n+  *").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "x"));
+        assert!(Day06.parse_rows("").is_err());
+    }
+
     #[test]
     fn test_example_input_running_part1() {
         assert_eq!(Day06.solve_part_one(EXAMPLE_INPUT), "4277556");
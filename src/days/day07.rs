@@ -2,27 +2,33 @@ pub struct Day07;
 use std::collections::HashSet;
 
 use crate::day::Day;
+use crate::trace::Tracer;
+use crate::utils::geometry::Point2;
+use crate::utils::parsing::{self, or_panic};
 
-type Point2D = (usize, usize);
+type Point2D = Point2<usize>;
 
 impl Day for Day07 {
     fn solve_part_one(&self, input: &str) -> String {
-        let (start, splitters, line_count) = self.parse_input(input);
-        let mut splitters_per_column = Vec::with_capacity(splitters.len());
+        self.trace_part_one(input, &mut Tracer::disabled())
+    }
 
-        for column in splitters {
-            let splitters_x: Vec<usize> = column.iter().map(|x| x.0).collect();
-            splitters_per_column.push(splitters_x);
-        }
+    fn solve_part_two(&self, input: &str) -> String {
+        self.trace_part_two(input, &mut Tracer::disabled())
+    }
 
-        let mut laser_locations = HashSet::from([start.1]);
+    fn trace_part_one(&self, input: &str, tracer: &mut Tracer) -> String {
+        let (start, splitters, line_count) = self.parse_input(input);
+        let splitters_per_column = splitter_lines(&splitters);
+
+        let mut laser_locations = HashSet::from([start.x]);
         let mut laser_split_counter = 0;
 
         for i in 0..line_count {
             let mut laser_locations_next_line = HashSet::new();
             for column in laser_locations.iter() {
                 let splitters = splitters_per_column.get(*column).unwrap();
-                if splitters.iter().any(|line| *line == i) {
+                if splitters.contains(&i) {
                     laser_locations_next_line.insert(column - 1);
                     laser_locations_next_line.insert(column + 1);
                     laser_split_counter += 1;
@@ -31,34 +37,77 @@ impl Day for Day07 {
                 }
             }
             laser_locations = laser_locations_next_line;
+            tracer.emit("beams", || {
+                let mut columns: Vec<usize> = laser_locations.iter().copied().collect();
+                columns.sort_unstable();
+                vec![("row", i.into()), ("columns", columns.into())]
+            });
         }
         format!("{}", laser_split_counter)
     }
 
-    fn solve_part_two(&self, input: &str) -> String {
-        todo!()
+    /// Every splitter forks a particle's timeline, so instead of the set of beam columns
+    /// this tracks how many timelines reach each column.
+    fn trace_part_two(&self, input: &str, tracer: &mut Tracer) -> String {
+        let (start, splitters, line_count) = self.parse_input(input);
+        let splitters_per_column = splitter_lines(&splitters);
+
+        let mut timelines = vec![0_u64; splitters_per_column.len()];
+        timelines[start.x] = 1;
+
+        for i in 0..line_count {
+            let mut timelines_next_line = vec![0; timelines.len()];
+            for (column, count) in timelines.iter().enumerate() {
+                if *count == 0 {
+                    continue;
+                }
+                if splitters_per_column[column].contains(&i) {
+                    timelines_next_line[column - 1] += count;
+                    timelines_next_line[column + 1] += count;
+                } else {
+                    timelines_next_line[column] += count;
+                }
+            }
+            timelines = timelines_next_line;
+            tracer.emit("timelines", || {
+                vec![
+                    ("row", i.into()),
+                    ("total", timelines.iter().sum::<u64>().into()),
+                ]
+            });
+        }
+        format!("{}", timelines.iter().sum::<u64>())
     }
 }
 
+/// The lines of the splitters in each column.
+fn splitter_lines(splitters: &[Vec<Point2D>]) -> Vec<Vec<usize>> {
+    splitters
+        .iter()
+        .map(|column| column.iter().map(|splitter| splitter.y).collect())
+        .collect()
+}
+
 impl Day07 {
     fn parse_input(&self, input: &str) -> (Point2D, Vec<Vec<Point2D>>, usize) {
-        let mut lines = input.lines();
-        let start_line = lines.next().unwrap();
-        // ASSUMING ASCII, this works as chars().len()
-        let line_length = start_line.len();
-        let start_column = start_line.chars().position(|x| x == 'S').unwrap();
-        let mut splitters_per_column = vec![vec![]; line_length];
-
-        let mut line_count = 0;
-        for (line_number, line) in lines.enumerate() {
-            for (column, char) in line.chars().enumerate() {
-                if char == '^' {
-                    splitters_per_column[column].push((line_number, column))
-                }
-            }
-            line_count += 1;
-        }
-        ((0, start_column), splitters_per_column, line_count + 1)
+        let grid = or_panic(parsing::char_grid(input, ".S^"));
+        let (start_column, _) = grid.find('S').expect("No start `S` in the manifold");
+        // Splitter lines are counted from the line below the start
+        let splitters_per_column = (0..grid.width())
+            .map(|column| {
+                grid.column(column)
+                    .enumerate()
+                    .skip(1)
+                    .filter(|(_, char)| **char == '^')
+                    .map(|(line, _)| Point2::new(column, line - 1))
+                    .collect()
+            })
+            .collect();
+        (
+            Point2::new(start_column, 0),
+            splitters_per_column,
+            grid.height(),
+        )
     }
 }
 
@@ -86,22 +135,22 @@ mod test {
     fn test_example_input_parsing() {
         let known_splitters = [
             vec![],
-            vec![(13, 1)],
-            vec![(11, 2)],
-            vec![(9, 3), (13, 3)],
-            vec![(7, 4)],
-            vec![(5, 5), (9, 5), (13, 5)],
-            vec![(3, 6), (7, 6), (11, 6)],
-            vec![(1, 7), (5, 7), (13, 7)],
-            vec![(3, 8)],
-            vec![(5, 9), (9, 9), (13, 9)],
-            vec![(7, 10)],
-            vec![(9, 11)],
-            vec![(11, 12)],
+            vec![(1, 13)],
+            vec![(2, 11)],
+            vec![(3, 9), (3, 13)],
+            vec![(4, 7)],
+            vec![(5, 5), (5, 9), (5, 13)],
+            vec![(6, 3), (6, 7), (6, 11)],
+            vec![(7, 1), (7, 5), (7, 13)],
+            vec![(8, 3)],
+            vec![(9, 5), (9, 9), (9, 13)],
+            vec![(10, 7)],
+            vec![(11, 9)],
+            vec![(12, 11)],
             vec![(13, 13)],
             vec![],
         ];
-        let known_start = (0, 7);
+        let known_start = (7, 0);
         let known_line_length = 16;
 
         let (parsed_start, parsed_splitters, parsed_line_length) = Day07.parse_input(EXAMPLE_INPUT);
@@ -117,6 +166,6 @@ mod test {
 
     #[test]
     fn test_example_input_running_part2() {
-        todo!()
+        assert_eq!("40", Day07.solve_part_two(EXAMPLE_INPUT));
     }
 }
@@ -1,132 +1,148 @@
-use crate::day::Day;
-use disjoint::DisjointSet;
-use std::{fmt::Display, num::ParseIntError, str::FromStr};
+use crate::day::{Day, Verification};
+use crate::trace::Tracer;
+use crate::utils::geometry::Point3;
+use crate::utils::kd_tree::KdTree;
+use crate::utils::mst::{Edge, Kruskal};
+use crate::utils::parsing::{self, or_panic};
 
 pub struct Day08;
 
-#[derive(Debug, PartialEq)]
-struct Point {
-    x: u64,
-    y: u64,
-    z: u64,
-}
-impl Point {
-    fn squared_distance(&self, b: &Point) -> u64 {
-        self.x.abs_diff(b.x).pow(2) + self.y.abs_diff(b.y).pow(2) + self.z.abs_diff(b.z).pow(2)
-    }
-}
+type Point = Point3<u64>;
 
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{},{})", self.x, self.y, self.z)
+impl Day for Day08 {
+    fn solve_part_one(&self, input: &str) -> String {
+        self.trace_part_one(input, &mut Tracer::disabled())
     }
-}
 
-impl PartialEq<(u64, u64, u64)> for Point {
-    fn eq(&self, other: &(u64, u64, u64)) -> bool {
-        let (x, y, z) = other;
-        self.x == *x && self.y == *y && self.z == *z
+    fn solve_part_two(&self, input: &str) -> String {
+        self.trace_part_two(input, &mut Tracer::disabled())
     }
-}
 
-impl From<(u64, u64, u64)> for Point {
-    fn from(value: (u64, u64, u64)) -> Self {
-        let (x, y, z) = value;
-        Point { x, y, z }
+    fn trace_part_one(&self, input: &str, tracer: &mut Tracer) -> String {
+        let points = self.parse_input(input);
+        format!("{}", self.largest_circuits_product(&points, 1000, tracer))
     }
-}
-
-impl FromStr for Point {
-    type Err = ParseIntError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut values = s.split(",").map(u64::from_str);
-
-        let x = values.next().unwrap()?;
-        let y = values.next().unwrap()?;
-        let z = values.next().unwrap()?;
-
-        Ok(Point { x, y, z })
+    fn trace_part_two(&self, input: &str, tracer: &mut Tracer) -> String {
+        let points = self.parse_input(input);
+        let latest_edge = self.find_final_edge(&points, tracer);
+        format!("{}", latest_edge.0.x * latest_edge.1.x)
     }
-}
 
-impl Day for Day08 {
-    fn solve_part_one(&self, input: &str) -> String {
+    fn verify_part_two(&self, input: &str) -> Option<Verification> {
         let points = self.parse_input(input);
-        let mut vertices = DisjointSet::with_len(points.len());
-        let mut pairs_with_distance = self.generate_pairs_with_distance(&points);
-        pairs_with_distance.sort_unstable_by_key(|x| x.0);
-
-        for i in 0..1000 {
-            let active_edge = pairs_with_distance[i].1;
-            let first_index = points.iter().position(|p| p == active_edge.0).unwrap();
-            let second_index = points.iter().position(|p| p == active_edge.1).unwrap();
+        let latest_edge = self.find_final_edge(&points, &mut Tracer::disabled());
+        let answer = latest_edge.0.x * latest_edge.1.x;
+        let outcome = check_final_edge(&points, latest_edge, answer);
+        Some(Verification::new(
+            answer,
+            format!("{} - {}", latest_edge.0, latest_edge.1),
+            outcome,
+        ))
+    }
+}
 
-            if !vertices.is_joined(first_index, second_index) {
-                vertices.join(first_index, second_index);
-            }
-        }
-        let mut circuits = vertices.sets();
-        circuits.sort_unstable_by_key(|set| set.len());
-        format!(
-            "{}",
-            circuits
-                .iter()
-                .rev()
-                .take(3)
-                .map(|x| x.len())
-                .product::<usize>()
-        )
+/// Checks that the junction boxes are connected by all pairs up to the witnessed edge's
+/// length, but not by the pairs strictly shorter than it, and that the edge gives `answer`.
+fn check_final_edge(points: &[Point], edge: (&Point, &Point), answer: u64) -> Result<(), String> {
+    if !points.contains(edge.0) || !points.contains(edge.1) || edge.0 == edge.1 {
+        return Err(format!(
+            "{} - {} is not a pair of junction boxes",
+            edge.0, edge.1
+        ));
+    }
+    if edge.0.x * edge.1.x != answer {
+        return Err(format!(
+            "Edge gives {}, not {}",
+            edge.0.x * edge.1.x,
+            answer
+        ));
+    }
+    let length = edge.0.euclidean_squared(edge.1);
+    if is_connected_within(points, length - 1) {
+        return Err("Junction boxes are connected before the edge".to_string());
     }
+    if !is_connected_within(points, length) {
+        return Err("Junction boxes are not connected by the edge".to_string());
+    }
+    Ok(())
+}
 
-    fn solve_part_two(&self, input: &str) -> String {
-        let points = self.parse_input(input);
-        let mut vertices = DisjointSet::with_len(points.len());
-        let mut pairs_with_distance = self.generate_pairs_with_distance(&points);
-        pairs_with_distance.sort_unstable_by_key(|x| x.0);
-        let mut circuits = vertices.sets();
-        let mut counter = 0;
-        let mut latest_edge = pairs_with_distance[0].1;
-
-        while circuits.len() != 1 {
-            let active_edge = pairs_with_distance[counter].1;
-            let first_index = points.iter().position(|p| p == active_edge.0).unwrap();
-            let second_index = points.iter().position(|p| p == active_edge.1).unwrap();
-
-            if !vertices.is_joined(first_index, second_index) {
-                vertices.join(first_index, second_index);
+/// Whether all points are connected using only pairs with squared distance at most `max`.
+fn is_connected_within(points: &[Point], max: u64) -> bool {
+    let mut visited = vec![false; points.len()];
+    let mut stack = vec![0];
+    visited[0] = true;
+    while let Some(current) = stack.pop() {
+        for (next, point) in points.iter().enumerate() {
+            if !visited[next] && points[current].euclidean_squared(point) <= max {
+                visited[next] = true;
+                stack.push(next);
             }
-
-            counter += 1;
-            circuits = vertices.sets();
-            latest_edge = active_edge;
         }
-
-        format!("{}", latest_edge.0.x * latest_edge.1.x)
     }
+    visited.iter().all(|x| *x)
+}
+
+fn trace_union(tracer: &mut Tracer, points: &[Point], edge: Edge) {
+    tracer.emit("union", || {
+        vec![
+            ("a", points[edge.a].to_string().into()),
+            ("b", points[edge.b].to_string().into()),
+            ("squared_distance", edge.weight.into()),
+        ]
+    });
 }
 
 impl Day08 {
     fn parse_input(&self, input: &str) -> Vec<Point> {
-        input
-            .lines()
-            .map(|line| Point::from_str(line).unwrap())
-            .collect()
+        let points = parsing::non_empty_lines(input)
+            .map(|line| line.tuple(',').map(|[x, y, z]| Point::new(x, y, z)))
+            .collect();
+        or_panic(points)
+    }
+
+    /// Connects the `connections` closest pairs and multiplies the sizes of the three
+    /// largest circuits.
+    fn largest_circuits_product(
+        &self,
+        points: &[Point],
+        connections: usize,
+        tracer: &mut Tracer,
+    ) -> usize {
+        let tree = KdTree::new(points);
+        let mut kruskal = Kruskal::new(points.len(), tree.pairs_by_distance());
+        for _ in 0..connections {
+            match kruskal.step() {
+                Some((edge, true)) => trace_union(tracer, points, edge),
+                Some((_, false)) => {}
+                None => break,
+            }
+        }
+        kruskal.components().largest_components(3).iter().product()
     }
 
-    fn generate_pairs_with_distance<'a>(
+    /// Connects the closest pairs until a single circuit remains and returns the last edge.
+    fn find_final_edge<'a>(
         &self,
         points: &'a [Point],
-    ) -> Vec<(u64, (&'a Point, &'a Point))> {
-        let mut result = vec![];
-        for (i, a) in points.iter().enumerate() {
-            for b in &points[i + 1..] {
-                let distance = a.squared_distance(b);
-                result.push((distance, (a, b)));
+        tracer: &mut Tracer,
+    ) -> (&'a Point, &'a Point) {
+        let tree = KdTree::new(points);
+        let mut kruskal = Kruskal::new(points.len(), tree.pairs_by_distance());
+        let mut latest_edge = (&points[0], &points[0]);
+
+        while !kruskal.is_connected() {
+            let (edge, joined) = kruskal
+                .step()
+                .expect("Ran out of pairs before connecting everything");
+            if joined {
+                trace_union(tracer, points, edge);
+                latest_edge = (&points[edge.a], &points[edge.b]);
             }
         }
 
-        result
+        latest_edge
     }
 }
 
@@ -190,21 +206,36 @@ mod test {
             (&points[2], &points[13]),
             (&points[7], &points[19]),
         ];
-        let mut values = Day08.generate_pairs_with_distance(points);
-        values.sort_unstable_by_key(|x| x.0);
-        for (value, pair) in values.iter().take(4).map(|x| x.1).zip(known_pairs) {
+        let tree = KdTree::new(points);
+        let values = tree
+            .pairs_by_distance()
+            .map(|(_, i, j)| (&points[i], &points[j]));
+        for (value, pair) in values.take(4).zip(known_pairs) {
             assert_eq!(value, pair)
         }
     }
 
-    // This requires a iteration count of 10, not 1000 as the actual input
-    // #[test]
-    // fn test_example_input_running_part1() {
-    //     assert_eq!("40", Day08.solve_part_one(EXAMPLE_INPUT));
-    // }
+    // The example connects 10 pairs, not 1000 as the actual input
+    #[test]
+    fn test_example_input_running_part1() {
+        let points = Day08.parse_input(EXAMPLE_INPUT);
+        let product = Day08.largest_circuits_product(&points, 10, &mut Tracer::disabled());
+        assert_eq!(40, product);
+    }
 
     #[test]
     fn test_example_input_running_part2() {
         assert_eq!("25272", Day08.solve_part_two(EXAMPLE_INPUT));
     }
+
+    #[test]
+    fn test_example_input_verification_part2() {
+        let verification = Day08.verify_part_two(EXAMPLE_INPUT).unwrap();
+        assert_eq!(verification.witness, "(216,146,977) - (117,168,530)");
+        assert_eq!(verification.outcome, Ok(()));
+
+        let points = Day08.parse_input(EXAMPLE_INPUT);
+        let too_short = (&points[0], &points[19]);
+        assert!(check_final_edge(&points, too_short, 162 * 425).is_err());
+    }
 }
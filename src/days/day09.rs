@@ -1,47 +1,213 @@
-use std::any;
-
-use crate::day::Day;
+use crate::day::{Day, Verification};
+use crate::utils::geometry::Point2;
+use crate::utils::grid::Grid;
+use crate::utils::parsing::{self, or_panic};
+use crate::utils::polygon::Polygon;
 pub struct Day09;
 
-type Point2D = (usize, usize);
+type Point2D = Point2<usize>;
 
 impl Day for Day09 {
     fn solve_part_one(&self, input: &str) -> String {
         let points = self.parse_input(input);
-        let max_area = self.find_max_area(points);
+        let (max_area, _) = self.find_max_area(&points);
         format!("{}", max_area)
     }
 
     fn solve_part_two(&self, input: &str) -> String {
-        todo!()
+        let points = self.parse_input(input);
+        let (max_area, _) = self.find_max_inside_area(&points);
+        format!("{}", max_area)
+    }
+
+    fn verify_part_one(&self, input: &str) -> Option<Verification> {
+        let points = self.parse_input(input);
+        let (max_area, corners) = self.find_max_area(&points);
+        let outcome = check_corners(&points, max_area, corners);
+        Some(Verification::new(
+            max_area,
+            format!("{} {}", corners.0, corners.1),
+            outcome,
+        ))
+    }
+
+    fn verify_part_two(&self, input: &str) -> Option<Verification> {
+        let points = self.parse_input(input);
+        let (max_area, corners) = self.find_max_inside_area(&points);
+        let outcome = check_corners(&points, max_area, corners)
+            .and_then(|_| check_tiles_covered(&points, corners));
+        Some(Verification::new(
+            max_area,
+            format!("{} {}", corners.0, corners.1),
+            outcome,
+        ))
+    }
+}
+
+/// One axis of the compressed floor. Every distinct red tile coordinate gets a cell, and so
+/// does the gap before, between and after them, however many tiles it spans.
+struct CompressedAxis {
+    coordinates: Vec<usize>,
+}
+
+impl CompressedAxis {
+    fn new(values: impl Iterator<Item = usize>) -> Self {
+        let mut coordinates: Vec<usize> = values.collect();
+        coordinates.sort_unstable();
+        coordinates.dedup();
+        CompressedAxis { coordinates }
+    }
+
+    fn len(&self) -> usize {
+        2 * self.coordinates.len() + 1
+    }
+
+    fn cell(&self, value: usize) -> usize {
+        2 * self.coordinates.binary_search(&value).unwrap() + 1
+    }
+
+    /// Number of tiles covered by `cell`.
+    fn tiles(&self, cell: usize) -> usize {
+        if cell % 2 == 1 {
+            1
+        } else if cell == 0 || cell == self.len() - 1 {
+            0
+        } else {
+            self.coordinates[cell / 2] - self.coordinates[cell / 2 - 1] - 1
+        }
+    }
+}
+
+/// Checks that both corners are red tiles and span a rectangle of the claimed area.
+fn check_corners(
+    points: &[Point2D],
+    area: usize,
+    corners: (Point2D, Point2D),
+) -> Result<(), String> {
+    for corner in [corners.0, corners.1] {
+        if !points.contains(&corner) {
+            return Err(format!("Corner {} is not a red tile", corner));
+        }
+    }
+    let width = corners.0.x.abs_diff(corners.1.x) + 1;
+    let height = corners.0.y.abs_diff(corners.1.y) + 1;
+    if width * height != area {
+        return Err(format!(
+            "Corners span {}x{} = {}, not {}",
+            width,
+            height,
+            width * height,
+            area
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that every tile of the rectangle is red or green, i.e. covered by the loop.
+fn check_tiles_covered(points: &[Point2D], corners: (Point2D, Point2D)) -> Result<(), String> {
+    let tile = |point: &Point2D| Point2::new(point.x as i64, point.y as i64);
+    let polygon = Polygon::new(points.iter().map(tile).collect());
+    if !polygon.is_rectilinear() {
+        return Err("Red tiles do not form a rectilinear loop".to_string());
+    }
+    match polygon.uncovered_tile(tile(&corners.0), tile(&corners.1)) {
+        Some(gap) => Err(format!(
+            "Tile {} of the rectangle is neither red nor green",
+            gap
+        )),
+        None => Ok(()),
     }
 }
 
 impl Day09 {
     fn parse_input(&self, input: &str) -> Vec<Point2D> {
-        let mut points = vec![];
-        for line in input.lines() {
-            let (x, y) = line.split_once(",").unwrap();
-            let x = x.parse::<usize>().unwrap();
-            let y = y.parse::<usize>().unwrap();
-            points.push((x, y));
-        }
-        points
+        let points = parsing::non_empty_lines(input)
+            .map(|line| line.tuple(',').map(|[x, y]| Point2D::new(x, y)))
+            .collect();
+        or_panic(points)
     }
 
-    fn find_max_area(&self, points: Vec<(usize, usize)>) -> usize {
+    fn find_max_area(&self, points: &[Point2D]) -> (usize, (Point2D, Point2D)) {
         let mut max_area = 0;
+        let mut corners = (points[0], points[0]);
 
         for (i, a) in points.iter().enumerate() {
             for b in &points[i + 1..] {
-                let area = (a.0.abs_diff(b.0) + 1) * (a.1.abs_diff(b.1) + 1);
+                let area = (a.x.abs_diff(b.x) + 1) * (a.y.abs_diff(b.y) + 1);
                 if area > max_area {
                     max_area = area;
+                    corners = (*a, *b);
+                }
+            }
+        }
+
+        (max_area, corners)
+    }
+
+    /// Largest rectangle with red corners whose tiles are all red or green. The floor is
+    /// compressed, the outside of the loop flood filled from the border and the number of
+    /// outside tiles in any rectangle read from prefix sums.
+    fn find_max_inside_area(&self, points: &[Point2D]) -> (usize, (Point2D, Point2D)) {
+        let xs = CompressedAxis::new(points.iter().map(|point| point.x));
+        let ys = CompressedAxis::new(points.iter().map(|point| point.y));
+        let compress = |point: &Point2D| (xs.cell(point.x), ys.cell(point.y));
+
+        let mut boundary = Grid::filled(xs.len(), ys.len(), false);
+        for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+            let ((x1, y1), (x2, y2)) = (compress(a), compress(b));
+            for x in x1.min(x2)..=x1.max(x2) {
+                for y in y1.min(y2)..=y1.max(y2) {
+                    boundary[(x, y)] = true;
+                }
+            }
+        }
+
+        let mut outside = Grid::filled(xs.len(), ys.len(), false);
+        outside[(0, 0)] = true;
+        let mut stack = vec![(0, 0)];
+        while let Some((x, y)) = stack.pop() {
+            for (nx, ny) in boundary.neighbours4(x, y) {
+                if !boundary[(nx, ny)] && !outside[(nx, ny)] {
+                    outside[(nx, ny)] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+
+        // outside_tiles[y][x] counts the outside tiles in cells left of x and above y
+        let mut outside_tiles = vec![vec![0; xs.len() + 1]; ys.len() + 1];
+        for y in 0..ys.len() {
+            for x in 0..xs.len() {
+                let tiles = if outside[(x, y)] {
+                    xs.tiles(x) * ys.tiles(y)
+                } else {
+                    0
+                };
+                outside_tiles[y + 1][x + 1] =
+                    tiles + outside_tiles[y][x + 1] + outside_tiles[y + 1][x] - outside_tiles[y][x];
+            }
+        }
+        let outside_between = |(x1, y1): (usize, usize), (x2, y2): (usize, usize)| {
+            let (x1, x2) = (x1.min(x2), x1.max(x2) + 1);
+            let (y1, y2) = (y1.min(y2), y1.max(y2) + 1);
+            outside_tiles[y2][x2] + outside_tiles[y1][x1]
+                - outside_tiles[y1][x2]
+                - outside_tiles[y2][x1]
+        };
+
+        let mut max_area = 0;
+        let mut corners = (points[0], points[0]);
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                let area = (a.x.abs_diff(b.x) + 1) * (a.y.abs_diff(b.y) + 1);
+                if area > max_area && outside_between(compress(a), compress(b)) == 0 {
+                    max_area = area;
+                    corners = (*a, *b);
                 }
             }
         }
 
-        max_area
+        (max_area, corners)
     }
 }
 
@@ -78,8 +244,54 @@ mod test {
         assert_eq!("50", Day09.solve_part_one(EXAMPLE_INPUT));
     }
 
+    #[test]
+    fn test_example_input_verification_part1() {
+        let verification = Day09.verify_part_one(EXAMPLE_INPUT).unwrap();
+        assert_eq!(verification.answer, "50");
+        assert_eq!(verification.outcome, Ok(()));
+    }
+
+    #[test]
+    fn test_check_corners_rejects_non_red_tile() {
+        let points = Day09.parse_input(EXAMPLE_INPUT);
+        let corners = (Point2::new(2, 5), Point2::new(11, 1));
+        assert!(check_corners(&points, 50, corners).is_ok());
+        assert!(check_corners(&points, 49, corners).is_err());
+        let not_red = (Point2::new(2, 5), Point2::new(11, 0));
+        assert!(check_corners(&points, 50, not_red).is_err());
+    }
+
     #[test]
     fn test_example_input_running_part2() {
-        todo!()
+        assert_eq!("24", Day09.solve_part_two(EXAMPLE_INPUT));
+    }
+
+    #[test]
+    fn test_example_input_verification_part2() {
+        let verification = Day09.verify_part_two(EXAMPLE_INPUT).unwrap();
+        assert_eq!(verification.answer, "24");
+        assert_eq!(verification.outcome, Ok(()));
+    }
+
+    #[test]
+    fn test_check_tiles_covered() {
+        let points = Day09.parse_input(EXAMPLE_INPUT);
+        let inside = (Point2::new(9, 5), Point2::new(2, 3));
+        assert!(check_tiles_covered(&points, inside).is_ok());
+        let outside = (Point2::new(2, 5), Point2::new(11, 1));
+        assert_eq!(
+            check_tiles_covered(&points, outside),
+            Err("Tile (2,1) of the rectangle is neither red nor green".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rectangle_over_boundary_tiles() {
+        // The notch between x = 2 and x = 3 is only a seam: both columns are green tiles,
+        // so the whole 7x5 rectangle is red or green
+        let input = "0,0\n6,0\n6,4\n3,4\n3,2\n2,2\n2,4\n0,4";
+        assert_eq!("35", Day09.solve_part_two(input));
+        let verification = Day09.verify_part_two(input).unwrap();
+        assert_eq!(verification.outcome, Ok(()));
     }
 }
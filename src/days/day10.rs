@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use crate::day::Day;
+use crate::utils::parsing::{self, Field, ParseResult, or_panic};
+pub struct Day10;
+
+/// Both parts try every subset of a machine's buttons, which stops finishing in reasonable
+/// time and memory well before the 32 a `u32` subset mask could hold.
+const MAX_BUTTONS: usize = 16;
+
+impl Day for Day10 {
+    fn solve_part_one(&self, input: &str) -> String {
+        let machines = self.parse_input(input);
+        let presses: usize = machines
+            .iter()
+            .map(|machine| {
+                machine
+                    .fewest_presses_for_lights()
+                    .expect("Indicator lights cannot be configured")
+            })
+            .sum();
+        format!("{}", presses)
+    }
+
+    fn solve_part_two(&self, input: &str) -> String {
+        let machines = self.parse_input(input);
+        let presses: usize = machines
+            .iter()
+            .map(|machine| {
+                machine
+                    .fewest_presses_for_joltages()
+                    .expect("Joltage levels cannot be reached")
+            })
+            .sum();
+        format!("{}", presses)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Machine {
+    /// Bit `i` is set if light `i` has to be on
+    lights: u32,
+    /// The counters each button toggles and increments
+    buttons: Vec<Vec<usize>>,
+    joltages: Vec<u64>,
+}
+
+/// The effect of pressing a set of buttons once each.
+struct Combination {
+    presses: usize,
+    increments: Vec<u64>,
+}
+
+impl Machine {
+    /// Every subset of buttons, grouped by the lights it leaves on. Pressing a button twice
+    /// undoes it for the lights, so a subset covers every way of configuring them.
+    fn combinations(&self) -> HashMap<u32, Vec<Combination>> {
+        let mut combinations: HashMap<u32, Vec<Combination>> = HashMap::new();
+        for subset in 0_u32..1 << self.buttons.len() {
+            let mut increments = vec![0; self.joltages.len()];
+            for (idx, button) in self.buttons.iter().enumerate() {
+                if subset & 1 << idx != 0 {
+                    for &counter in button {
+                        increments[counter] += 1;
+                    }
+                }
+            }
+            let lights = increments
+                .iter()
+                .enumerate()
+                .filter(|(_, increment)| *increment % 2 == 1)
+                .fold(0, |lights, (counter, _)| lights | 1 << counter);
+            combinations.entry(lights).or_default().push(Combination {
+                presses: subset.count_ones() as usize,
+                increments,
+            });
+        }
+        combinations
+    }
+
+    fn fewest_presses_for_lights(&self) -> Option<usize> {
+        self.combinations()
+            .get(&self.lights)?
+            .iter()
+            .map(|combination| combination.presses)
+            .min()
+    }
+
+    /// Any solution presses some set of buttons an odd number of times and every button an
+    /// even number of times on top. That set must match the parity of the targets, and what
+    /// is left is the same problem with all targets halved and every press counting double.
+    fn fewest_presses_for_joltages(&self) -> Option<usize> {
+        let combinations = self.combinations();
+        let mut cache = HashMap::new();
+        fewest_presses_for(&self.joltages, &combinations, &mut cache)
+    }
+}
+
+fn fewest_presses_for(
+    targets: &[u64],
+    combinations: &HashMap<u32, Vec<Combination>>,
+    cache: &mut HashMap<Vec<u64>, Option<usize>>,
+) -> Option<usize> {
+    if targets.iter().all(|target| *target == 0) {
+        return Some(0);
+    }
+    if let Some(cached_value) = cache.get(targets) {
+        return *cached_value;
+    }
+    let parity = targets
+        .iter()
+        .enumerate()
+        .filter(|(_, target)| *target % 2 == 1)
+        .fold(0, |lights, (counter, _)| lights | 1 << counter);
+
+    let mut best = None;
+    for combination in combinations.get(&parity).into_iter().flatten() {
+        let fits = targets
+            .iter()
+            .zip(&combination.increments)
+            .all(|(target, increment)| increment <= target);
+        if !fits {
+            continue;
+        }
+        let halved: Vec<u64> = targets
+            .iter()
+            .zip(&combination.increments)
+            .map(|(target, increment)| (target - increment) / 2)
+            .collect();
+        if let Some(presses) = fewest_presses_for(&halved, combinations, cache) {
+            let total = combination.presses + 2 * presses;
+            best = Some(best.map_or(total, |best: usize| best.min(total)));
+        }
+    }
+    cache.insert(targets.to_vec(), best);
+    best
+}
+
+impl Day10 {
+    fn parse_input(&self, input: &str) -> Vec<Machine> {
+        let machines = parsing::non_empty_lines(input)
+            .map(|line| self.parse_machine(line))
+            .collect();
+        or_panic(machines)
+    }
+
+    /// Parses `[.##.] (3) (1,3) (2) {3,5,4,7}`
+    fn parse_machine(&self, line: Field) -> ParseResult<Machine> {
+        let mut fields = line.split(' ').filter(|field| !field.is_empty());
+        let missing = || line.error("Expected lights, buttons and joltages");
+
+        let lights_field = fields.next().ok_or_else(missing)?.delimited('[', ']')?;
+        let light_count = lights_field.text.chars().count();
+        if light_count > u32::BITS as usize {
+            return Err(lights_field.error(format!("At most {} lights are supported", u32::BITS)));
+        }
+        let mut lights = 0;
+        for (idx, light) in lights_field.text.chars().enumerate() {
+            match light {
+                '#' => lights |= 1 << idx,
+                '.' => {}
+                _ => return Err(lights_field.error("Expected only `.` and `#`")),
+            }
+        }
+
+        let mut buttons = vec![];
+        let mut joltages = None;
+        for field in fields {
+            if joltages.is_some() {
+                return Err(field.error("Unexpected text after the joltages"));
+            }
+            if field.text.starts_with('{') {
+                let values: ParseResult<Vec<u64>> = field
+                    .delimited('{', '}')?
+                    .split(',')
+                    .map(|value| value.int())
+                    .collect();
+                joltages = Some(values?);
+                continue;
+            }
+            let counters: Vec<usize> = field
+                .delimited('(', ')')?
+                .split(',')
+                .map(|counter| counter.int())
+                .collect::<ParseResult<_>>()?;
+            if counters.iter().any(|counter| *counter >= light_count) {
+                return Err(field.error(format!("Button wired beyond {} lights", light_count)));
+            }
+            if buttons.len() == MAX_BUTTONS {
+                return Err(field.error(format!(
+                    "At most {} buttons, every subset of them is tried",
+                    MAX_BUTTONS
+                )));
+            }
+            buttons.push(counters);
+        }
+
+        let joltages = joltages.ok_or_else(missing)?;
+        if joltages.len() != light_count {
+            return Err(line.error(format!(
+                "Expected {} joltages, found {}",
+                light_count,
+                joltages.len()
+            )));
+        }
+        Ok(Machine {
+            lights,
+            buttons,
+            joltages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const EXAMPLE_INPUT: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
+
+    #[test]
+    fn test_example_input_parsing() {
+        let machines = Day10.parse_input(EXAMPLE_INPUT);
+        assert_eq!(machines.len(), 3);
+        assert_eq!(
+            machines[0],
+            Machine {
+                lights: 0b0110,
+                buttons: vec![
+                    vec![3],
+                    vec![1, 3],
+                    vec![2],
+                    vec![2, 3],
+                    vec![0, 2],
+                    vec![0, 1]
+                ],
+                joltages: vec![3, 5, 4, 7],
+            }
+        );
+        assert_eq!(machines[2].lights, 0b101110);
+    }
+
+    #[test]
+    fn test_parsing_errors() {
+        let error = Day10
+            .parse_machine(Field::new("[.#] (0,2) {1,1}"))
+            .unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (6, "(0,2)"));
+        assert!(Day10.parse_machine(Field::new("[.#] (0,1)")).is_err());
+        assert!(Day10.parse_machine(Field::new("[.x] (0,1) {1,1}")).is_err());
+    }
+
+    #[test]
+    fn test_too_many_lights_and_buttons() {
+        let lights = ".".repeat(33);
+        let line = format!("[{}] (0) {{{}}}", lights, vec!["0"; 33].join(","));
+        let error = Day10.parse_machine(Field::new(&line)).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (2, lights.as_str()));
+
+        let line = format!("[.] {} {{0}}", vec!["(0)"; MAX_BUTTONS + 1].join(" "));
+        let error = Day10.parse_machine(Field::new(&line)).unwrap_err();
+        assert_eq!(error.column, 5 + MAX_BUTTONS * 4);
+        assert!(error.message.starts_with("At most 16 buttons"));
+
+        let buttons = vec!["(31)"; MAX_BUTTONS].join(" ");
+        let line = format!(
+            "[{}] {} {{{}}}",
+            ".".repeat(32),
+            buttons,
+            vec!["0"; 32].join(",")
+        );
+        assert!(Day10.parse_machine(Field::new(&line)).is_ok());
+    }
+
+    #[test]
+    fn test_example_machines() {
+        let machines = Day10.parse_input(EXAMPLE_INPUT);
+        let lights: Vec<_> = machines
+            .iter()
+            .map(|machine| machine.fewest_presses_for_lights())
+            .collect();
+        assert_eq!(lights, [Some(2), Some(3), Some(2)]);
+        let joltages: Vec<_> = machines
+            .iter()
+            .map(|machine| machine.fewest_presses_for_joltages())
+            .collect();
+        assert_eq!(joltages, [Some(10), Some(12), Some(11)]);
+    }
+
+    #[test]
+    fn test_example_input_running_part1() {
+        assert_eq!("7", Day10.solve_part_one(EXAMPLE_INPUT));
+    }
+
+    #[test]
+    fn test_example_input_running_part2() {
+        assert_eq!("33", Day10.solve_part_two(EXAMPLE_INPUT));
+    }
+}
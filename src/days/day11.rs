@@ -1,76 +1,38 @@
 use std::collections::HashMap;
 
 use crate::day::Day;
+use crate::utils::graph::{Graph, PathCounter, WaypointOrder};
+use crate::utils::parsing::{self, or_panic};
 pub struct Day11;
 
 impl Day for Day11 {
     fn solve_part_one(&self, input: &str) -> String {
-        let mut cache: HashMap<&str, usize> = HashMap::new();
-        let start = "you";
-        let end = "out";
+        let graph: Graph = self.parse_input(input).into_iter().collect();
+        let mut counter = path_counter(&graph);
 
-        let edges = self.parse_input(input);
-        let path_count = dfs(&edges, start, end, &mut cache);
-
-        format!("{}", path_count)
+        format!("{}", counter.count_between("you", "out"))
     }
 
     fn solve_part_two(&self, input: &str) -> String {
-        // Caching is specific to the end node
-        let mut cache_out: HashMap<&str, usize> = HashMap::new();
-        let mut cache_fft: HashMap<&str, usize> = HashMap::new();
-        let mut cache_dac: HashMap<&str, usize> = HashMap::new();
-
-        let start = "svr";
-        let end = "out";
+        let graph: Graph = self.parse_input(input).into_iter().collect();
+        let counter = path_counter(&graph);
+        let path_count =
+            counter.count_via_names("svr", "out", &["dac", "fft"], WaypointOrder::Any, &[]);
 
-        let mut edges = self.parse_input(input);
-        // "out" never appears on the left side and as such is never generated in the input parsing
-        edges.insert("out", vec![]);
-
-        let paths_dac_first = dfs(&edges, start, "dac", &mut cache_dac)
-            * dfs(&edges, "dac", "fft", &mut cache_fft)
-            * dfs(&edges, "fft", end, &mut cache_out);
-        let paths_fft_first = dfs(&edges, start, "fft", &mut cache_fft)
-            * dfs(&edges, "fft", "dac", &mut cache_dac)
-            * dfs(&edges, "dac", end, &mut cache_out);
-
-        format!("{}", paths_dac_first + paths_fft_first)
+        format!("{}", path_count)
     }
 }
 
-fn dfs<'a>(
-    edges: &HashMap<&'a str, Vec<&'a str>>,
-    start: &'a str,
-    end: &str,
-    cache: &mut HashMap<&'a str, usize>,
-) -> usize {
-    if let Some(cached_value) = cache.get(start) {
-        return *cached_value;
-    }
-
-    if start == end {
-        return 1;
-    }
-
-    let count = edges[start]
-        .iter()
-        .map(|output| dfs(edges, output, end, cache))
-        .sum();
-    cache.insert(start, count);
-
-    count
+fn path_counter<'g, 'a>(graph: &'g Graph<'a>) -> PathCounter<'g, 'a> {
+    PathCounter::new(graph).unwrap_or_else(|cycle| panic!("Devices are wired in a loop: {}", cycle))
 }
 
 impl Day11 {
     fn parse_input<'a>(&self, input: &'a str) -> HashMap<&'a str, Vec<&'a str>> {
-        let mut result = HashMap::new();
-        for line in input.lines() {
-            let (name, outputs_raw) = line.split_once(":").unwrap();
-            let output_devices: Vec<_> = outputs_raw.split_whitespace().collect();
-            result.insert(name, output_devices);
-        }
-        result
+        let edges = parsing::non_empty_lines(input)
+            .map(|line| line.adjacency())
+            .collect();
+        or_panic(edges)
     }
 }
 
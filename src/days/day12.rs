@@ -0,0 +1,415 @@
+use std::collections::HashSet;
+
+use crate::day::Day;
+use crate::utils::parsing::{self, Field, ParseResult, or_panic};
+pub struct Day12;
+
+impl Day for Day12 {
+    fn solve_part_one(&self, input: &str) -> String {
+        let (shapes, regions) = self.parse_input(input);
+        let orientations: Vec<Vec<Orientation>> = shapes.iter().map(orientations).collect();
+        let count = regions
+            .iter()
+            .filter(|region| region.fits(&orientations))
+            .count();
+        format!("{}", count)
+    }
+
+    fn solve_part_two(&self, _input: &str) -> String {
+        // The last day only has one puzzle, the second star comes with the other 23
+        "Merry Christmas!".to_string()
+    }
+}
+
+/// The cells of a present as `(x, y)` offsets.
+type Shape = Vec<(i32, i32)>;
+
+/// A rotated or flipped shape, with offsets relative to its first cell in row-major order.
+type Orientation = Vec<(i32, i32)>;
+
+#[derive(Debug, PartialEq)]
+struct Region {
+    width: usize,
+    height: usize,
+    /// How many presents of each shape have to fit
+    counts: Vec<usize>,
+}
+
+/// All distinct rotations and reflections of `shape`.
+fn orientations(shape: &Shape) -> Vec<Orientation> {
+    let mut seen = HashSet::new();
+    let mut result = vec![];
+    let mut current = shape.clone();
+    for flip in 0..2 {
+        for _ in 0..4 {
+            current = current.iter().map(|&(x, y)| (-y, x)).collect();
+            let mut cells: Vec<(i32, i32)> = current.iter().map(|&(x, y)| (y, x)).collect();
+            // Sorted as (y, x) so that the first cell is the first in row-major order
+            cells.sort_unstable();
+            let (anchor_y, anchor_x) = cells[0];
+            let normalised: Orientation = cells
+                .iter()
+                .map(|&(y, x)| (x - anchor_x, y - anchor_y))
+                .collect();
+            if seen.insert(normalised.clone()) {
+                result.push(normalised);
+            }
+        }
+        if flip == 0 {
+            current = current.iter().map(|&(x, y)| (-x, y)).collect();
+        }
+    }
+    result
+}
+
+/// Width and height of a box that holds every shape in its first orientation.
+fn bounding_box(orientations: &[Vec<Orientation>]) -> (usize, usize) {
+    let extent = |values: Vec<i32>| {
+        let min = values.iter().min().unwrap();
+        let max = values.iter().max().unwrap();
+        (max - min + 1) as usize
+    };
+    orientations
+        .iter()
+        .map(|shape| {
+            let width = extent(shape[0].iter().map(|&(x, _)| x).collect());
+            let height = extent(shape[0].iter().map(|&(_, y)| y).collect());
+            (width, height)
+        })
+        .fold((1, 1), |(width, height), (w, h)| {
+            (width.max(w), height.max(h))
+        })
+}
+
+impl Region {
+    fn fits(&self, orientations: &[Vec<Orientation>]) -> bool {
+        let cells_needed: usize = self
+            .counts
+            .iter()
+            .zip(orientations)
+            .map(|(count, shape)| count * shape[0].len())
+            .sum();
+        let area = self.width * self.height;
+        if cells_needed > area {
+            return false;
+        }
+        // Every present fits in a box as large as the largest bounding box, so presents
+        // never have to interlock if the region holds enough boxes
+        let presents: usize = self.counts.iter().sum();
+        let (box_width, box_height) = bounding_box(orientations);
+        let boxes = (self.width / box_width) * (self.height / box_height);
+        let turned_boxes = (self.width / box_height) * (self.height / box_width);
+        if boxes.max(turned_boxes) >= presents {
+            return true;
+        }
+        // Orientations include all rotations, so the region can be turned to scan along its
+        // short side, which keeps the frontier of unsettled cells small
+        let (width, height) = (self.width.min(self.height), self.width.max(self.height));
+        let mut search = Packing {
+            width,
+            height,
+            filled: vec![false; area],
+            counts: self.counts.clone(),
+            orientations,
+            reach: reach(orientations, width),
+            failed: HashSet::new(),
+        };
+        search.pack(0, presents, area - cells_needed)
+    }
+}
+
+/// Backtracking search that fills the region cell by cell in row-major order. The first
+/// free cell is either covered by a present placed with its first cell there, or left
+/// empty, which spends one unit of the slack between region area and present cells.
+/// Everything before the first free cell is settled, so a state is fully described by that
+/// cell, the presents left and the filled cells a present placed there could reach.
+struct Packing<'a> {
+    width: usize,
+    height: usize,
+    filled: Vec<bool>,
+    counts: Vec<usize>,
+    orientations: &'a [Vec<Orientation>],
+    /// How many cells past its anchor any orientation can cover in row-major order
+    reach: usize,
+    failed: HashSet<(usize, Vec<usize>, Vec<bool>)>,
+}
+
+fn reach(orientations: &[Vec<Orientation>], width: usize) -> usize {
+    orientations
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|&(dx, dy)| (dy * width as i32 + dx).max(0) as usize)
+        .max()
+        .unwrap_or(0)
+}
+
+impl Packing<'_> {
+    fn pack(&mut self, from: usize, remaining: usize, slack: usize) -> bool {
+        if remaining == 0 {
+            return true;
+        }
+        let Some(cell) = (from..self.filled.len()).find(|cell| !self.filled[*cell]) else {
+            return false;
+        };
+        let y = cell / self.width;
+        // Presents are anchored at their top row, so once the shortest one left no longer
+        // fits below this row nothing can be placed here or anywhere after
+        if y + self.shortest_remaining() > self.height {
+            return false;
+        }
+
+        let frontier = self.filled[cell..(cell + self.reach + 1).min(self.filled.len())].to_vec();
+        let state = (cell, self.counts.clone(), frontier);
+        if self.failed.contains(&state) {
+            return false;
+        }
+        let packed = self.place_or_skip(cell, remaining, slack);
+        if !packed {
+            self.failed.insert(state);
+        }
+        packed
+    }
+
+    fn place_or_skip(&mut self, cell: usize, remaining: usize, slack: usize) -> bool {
+        let (x, y) = ((cell % self.width) as i32, (cell / self.width) as i32);
+        for shape in 0..self.counts.len() {
+            if self.counts[shape] == 0 {
+                continue;
+            }
+            for orientation in &self.orientations[shape] {
+                let Some(cells) = self.cells_at(orientation, x, y) else {
+                    continue;
+                };
+                self.set(&cells, true);
+                self.counts[shape] -= 1;
+                let packed = self.pack(cell + 1, remaining - 1, slack);
+                self.counts[shape] += 1;
+                self.set(&cells, false);
+                if packed {
+                    return true;
+                }
+            }
+        }
+
+        if slack > 0 {
+            self.filled[cell] = true;
+            let packed = self.pack(cell + 1, remaining, slack - 1);
+            self.filled[cell] = false;
+            return packed;
+        }
+        false
+    }
+
+    /// Height of the shortest orientation among the presents still to be placed.
+    fn shortest_remaining(&self) -> usize {
+        self.counts
+            .iter()
+            .zip(self.orientations)
+            .filter(|(count, _)| **count > 0)
+            .flat_map(|(_, orientations)| orientations)
+            .map(|orientation| {
+                orientation.iter().map(|&(_, dy)| dy).max().unwrap_or(0) as usize + 1
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// The region cells covered by `orientation` anchored at `(x, y)`, if all are free.
+    fn cells_at(&self, orientation: &Orientation, x: i32, y: i32) -> Option<Vec<usize>> {
+        orientation
+            .iter()
+            .map(|&(dx, dy)| {
+                let (cx, cy) = (x + dx, y + dy);
+                if cx < 0 || cx >= self.width as i32 || cy >= self.height as i32 {
+                    return None;
+                }
+                let cell = cy as usize * self.width + cx as usize;
+                (!self.filled[cell]).then_some(cell)
+            })
+            .collect()
+    }
+
+    fn set(&mut self, cells: &[usize], value: bool) {
+        for &cell in cells {
+            self.filled[cell] = value;
+        }
+    }
+}
+
+impl Day12 {
+    fn parse_input(&self, input: &str) -> (Vec<Shape>, Vec<Region>) {
+        let mut sections = parsing::sections(input);
+        let regions = sections.pop().expect("No regions in the input");
+        let shapes: ParseResult<Vec<Shape>> = sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| self.parse_shape(*section, index))
+            .collect();
+        let shapes = or_panic(shapes);
+        let regions: ParseResult<Vec<Region>> = regions
+            .lines()
+            .map(|line| self.parse_region(line, shapes.len()))
+            .collect();
+        (shapes, or_panic(regions))
+    }
+
+    /// Parses `index:` followed by the shape drawn with `#` and `.`
+    fn parse_shape(&self, section: Field, index: usize) -> ParseResult<Shape> {
+        let mut lines = section.lines();
+        let header = lines.next().ok_or_else(|| section.error("Empty shape"))?;
+        let (number, rest) = header.split_once(':')?;
+        if number.int::<usize>()? != index || !rest.is_empty() {
+            return Err(header.error(format!("Expected `{}:`", index)));
+        }
+        let mut cells = vec![];
+        for (y, line) in lines.enumerate() {
+            for (x, char) in line.text.chars().enumerate() {
+                match char {
+                    '#' => cells.push((x as i32, y as i32)),
+                    '.' => {}
+                    _ => return Err(line.error("Expected only `#` and `.`")),
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Err(section.error("Shape without cells"));
+        }
+        Ok(cells)
+    }
+
+    /// Parses `12x5: 1 0 1 0 2 2`
+    fn parse_region(&self, line: Field, shape_count: usize) -> ParseResult<Region> {
+        let (size, counts_field) = line.split_once(':')?;
+        let (width, height) = size.split_once('x')?;
+        let counts: ParseResult<Vec<usize>> = counts_field
+            .split(' ')
+            .filter(|count| !count.is_empty())
+            .map(|count| count.int())
+            .collect();
+        let counts = counts?;
+        if counts.len() != shape_count {
+            return Err(counts_field.trim().error(format!(
+                "Expected {} counts, one per shape, found {}",
+                shape_count,
+                counts.len()
+            )));
+        }
+        Ok(Region {
+            width: width.int()?,
+            height: height.int()?,
+            counts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const EXAMPLE_INPUT: &str = "0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2";
+
+    #[test]
+    fn test_example_input_parsing() {
+        let (shapes, regions) = Day12.parse_input(EXAMPLE_INPUT);
+        assert_eq!(shapes.len(), 6);
+        assert_eq!(
+            shapes[5],
+            [(0, 0), (1, 0), (2, 0), (1, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(
+            regions[1],
+            Region {
+                width: 12,
+                height: 5,
+                counts: vec![1, 0, 1, 0, 2, 2]
+            }
+        );
+    }
+
+    #[test]
+    fn test_orientations() {
+        let (shapes, _) = Day12.parse_input(EXAMPLE_INPUT);
+        let counts: Vec<usize> = shapes
+            .iter()
+            .map(|shape| orientations(shape).len())
+            .collect();
+        assert_eq!(counts, [8, 8, 2, 4, 4, 2]);
+        let square = vec![(0, 0), (1, 0), (0, 1), (1, 1)];
+        assert_eq!(orientations(&square).len(), 1);
+    }
+
+    #[test]
+    fn test_example_regions() {
+        let (shapes, regions) = Day12.parse_input(EXAMPLE_INPUT);
+        let orientations: Vec<Vec<Orientation>> = shapes.iter().map(orientations).collect();
+        let fits: Vec<bool> = regions
+            .iter()
+            .map(|region| region.fits(&orientations))
+            .collect();
+        assert_eq!(fits, [true, true, false]);
+    }
+
+    #[test]
+    fn test_example_input_running_part1() {
+        assert_eq!("2", Day12.solve_part_one(EXAMPLE_INPUT));
+    }
+
+    #[test]
+    fn test_region_count_mismatch() {
+        let error = Day12.parse_region(Field::new("4x4: 0 0 2"), 6).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (6, "0 0 2"));
+    }
+
+    #[test]
+    fn test_wide_shapes_skip_box_shortcut() {
+        // A 1x4 bar has room in a 3x3 region by area and by 3x3 boxes, but not by shape
+        let bar = vec![(0, 0), (1, 0), (2, 0), (3, 0)];
+        let orientations = [orientations(&bar)];
+        let (width, height) = bounding_box(&orientations);
+        assert_eq!((width.min(height), width.max(height)), (1, 4));
+        let region = Region {
+            width: 3,
+            height: 3,
+            counts: vec![1],
+        };
+        assert!(!region.fits(&orientations));
+        let region = Region {
+            width: 4,
+            height: 3,
+            counts: vec![3],
+        };
+        assert!(region.fits(&orientations));
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::Display;
+use std::io::Write;
+
+use crate::utils::geometry::{Point2, Point3};
+
+/// A field value of a trace event, rendered as JSON.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    Int(i64),
+    UInt(u64),
+    Str(String),
+    List(Vec<Value>),
+}
+
+macro_rules! value_from {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(impl From<$source> for Value {
+            fn from(value: $source) -> Self {
+                Value::$variant(value as $target)
+            }
+        })+
+    };
+}
+
+value_from!(Int, i64, i8, i16, i32, i64);
+value_from!(UInt, u64, u8, u16, u32, u64, usize);
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(value: Vec<T>) -> Self {
+        Value::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<A: Into<Value>, B: Into<Value>> From<(A, B)> for Value {
+    fn from(value: (A, B)) -> Self {
+        Value::List(vec![value.0.into(), value.1.into()])
+    }
+}
+
+impl<T: Into<Value>> From<Point2<T>> for Value {
+    fn from(value: Point2<T>) -> Self {
+        Value::List(vec![value.x.into(), value.y.into()])
+    }
+}
+
+impl<T: Into<Value>> From<Point3<T>> for Value {
+    fn from(value: Point3<T>) -> Self {
+        Value::List(vec![value.x.into(), value.y.into(), value.z.into()])
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::UInt(value) => write!(f, "{}", value),
+            Value::Str(value) => {
+                write!(f, "\"")?;
+                for char in value.chars() {
+                    match char {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Receives structured events from a solver and writes them as JSON lines.
+/// A disabled tracer drops every event without building its fields.
+pub struct Tracer<'a> {
+    sink: Option<Box<dyn Write + 'a>>,
+    day: u8,
+    part: u8,
+}
+
+impl<'a> Tracer<'a> {
+    pub fn new(sink: impl Write + 'a, day: u8) -> Tracer<'a> {
+        Tracer {
+            sink: Some(Box::new(sink)),
+            day,
+            part: 0,
+        }
+    }
+
+    pub fn disabled() -> Tracer<'a> {
+        Tracer {
+            sink: None,
+            day: 0,
+            part: 0,
+        }
+    }
+
+    pub fn set_part(&mut self, part: u8) {
+        self.part = part;
+    }
+
+    pub fn emit(&mut self, event: &str, fields: impl FnOnce() -> Vec<(&'static str, Value)>) {
+        let Some(sink) = self.sink.as_mut() else {
+            return;
+        };
+        let mut line = format!(
+            "{{\"day\":{},\"part\":{},\"event\":{}",
+            self.day,
+            self.part,
+            Value::from(event)
+        );
+        for (key, value) in fields() {
+            line += &format!(",{}:{}", Value::from(key), value);
+        }
+        line += "}";
+        writeln!(sink, "{}", line).expect("Couldn't write trace event");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_value_rendering() {
+        assert_eq!(Value::from(-3).to_string(), "-3");
+        assert_eq!(Value::from("a\"b\n").to_string(), "\"a\\\"b\\n\"");
+        assert_eq!(
+            Value::from(vec![(1_u8, 2_u8), (3, 4)]).to_string(),
+            "[[1,2],[3,4]]"
+        );
+    }
+
+    #[test]
+    fn test_emit_json_line() {
+        let mut buffer = vec![];
+        let mut tracer = Tracer::new(&mut buffer, 1);
+        tracer.set_part(2);
+        tracer.emit("dial", || vec![("position", 52.into())]);
+        drop(tracer);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"day\":1,\"part\":2,\"event\":\"dial\",\"position\":52}\n"
+        );
+    }
+
+    #[test]
+    fn test_disabled_tracer_skips_fields() {
+        let mut tracer = Tracer::disabled();
+        tracer.emit("never", || unreachable!());
+    }
+}
@@ -1,47 +1,135 @@
 use std::str::FromStr;
+mod bench;
 mod days;
+mod profiling;
+mod status;
+mod utils;
 
-use days::day::Day;
+use days::day::{Day, Verification};
+use days::trace::Tracer;
 use days::*;
 use std::env;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
 use std::time::Instant;
 
+/// Number of days in the 2025 calendar
+const DAY_COUNT: u8 = 12;
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
+    if args.len() < 2 {
         panic!("Please provide the day to run as a command-line argument.");
     }
 
-    let day_number = u8::from_str(&args[1]).expect("Unparseable day given");
+    match args[1].as_str() {
+        "bench" => bench::bench_command(&args[2..]),
+        "bench-diff" => bench::diff_command(&args[2..]),
+        "status" => status::status_command(),
+        "accept" => status::accept_command(&args[2..]),
+        _ => run_day(&args[1..]),
+    }
+}
 
-    let day = get_day(day_number);
-    let input =
-        fs::read_to_string(format!("inputs/{:02}.txt", day_number)).expect("Couldn't read file");
+fn run_day(args: &[String]) {
+    let day_number = u8::from_str(&args[0]).expect("Unparseable day given");
+    let profile = args[1..].iter().any(|arg| arg == "--profile");
+    let verify = args[1..].iter().any(|arg| arg == "--verify");
+    let mut tracer = create_tracer(&args[1..], day_number);
+
+    let day = get_day(day_number).expect("Day not implemented");
+    let input = read_input(day_number).expect("Couldn't read file");
 
-    let time = Instant::now();
     let mut total_runtime = 0.0;
 
     println!("\n=== Day {:02} ===", day_number);
 
-    println!("Part 1: {}", day.solve_part_one(&input));
-    let elapsed_ms = time.elapsed().as_millis() as f64;
-    println!("  · Elapsed: {:.4} ms", elapsed_ms);
-
+    let part_one = || {
+        tracer.set_part(1);
+        day.trace_part_one(&input, &mut tracer)
+    };
+    let (answer, elapsed_ms) = run_part(1, part_one, profile);
     total_runtime += elapsed_ms;
+    if verify {
+        report_verification(&answer, day.verify_part_one(&input));
+    }
+    let part_two = || {
+        tracer.set_part(2);
+        day.trace_part_two(&input, &mut tracer)
+    };
+    let (answer, elapsed_ms) = run_part(2, part_two, profile);
+    total_runtime += elapsed_ms;
+    if verify {
+        report_verification(&answer, day.verify_part_two(&input));
+    }
 
+    println!("Total runtime: {:.4} ms", total_runtime);
+}
+
+/// `--trace` writes trace events to stdout, `--trace=<path>` writes them to a file.
+fn create_tracer(args: &[String], day_number: u8) -> Tracer<'static> {
+    for arg in args {
+        if arg == "--trace" {
+            return Tracer::new(io::stdout(), day_number);
+        }
+        if let Some(path) = arg.strip_prefix("--trace=") {
+            let file = File::create(path).expect("Couldn't create trace file");
+            return Tracer::new(BufWriter::new(file), day_number);
+        }
+    }
+    Tracer::disabled()
+}
+
+/// Runs a single part, printing its answer and runtime. Returns the answer and the elapsed
+/// time in ms.
+fn run_part(part: u8, solve: impl FnOnce() -> String, profile: bool) -> (String, f64) {
     let time = Instant::now();
+    let (answer, allocations) = if profile {
+        let (answer, stats) = profiling::measure(solve);
+        (answer, Some(stats))
+    } else {
+        (solve(), None)
+    };
+    let elapsed_ms = time.elapsed().as_secs_f64() * 1000.0;
 
-    println!("Part 2: {}", day.solve_part_two(&input));
-    let elapsed_ms = time.elapsed().as_millis() as f64;
+    println!("Part {}: {}", part, answer);
     println!("  · Elapsed: {:.4} ms", elapsed_ms);
+    if let Some(stats) = allocations {
+        println!("  · Memory: {}", stats);
+    }
 
-    total_runtime += elapsed_ms;
-    println!("Total runtime: {} ms", total_runtime);
+    (answer, elapsed_ms)
+}
+
+/// Prints the witness and whether it backs up `answer`, the one the solver printed.
+fn report_verification(answer: &str, verification: Option<Verification>) {
+    const MAX_WITNESS_LENGTH: usize = 80;
+    let Some(verification) = verification else {
+        println!("  · Witness: not available");
+        return;
+    };
+
+    let mut witness = verification.witness;
+    if witness.chars().count() > MAX_WITNESS_LENGTH {
+        witness = witness.chars().take(MAX_WITNESS_LENGTH).collect::<String>() + "…";
+    }
+    println!("  · Witness: {}", witness);
+    match verification.outcome {
+        Err(reason) => println!("  · REJECTED: {}", reason),
+        Ok(()) if verification.answer != answer => println!(
+            "  · REJECTED: witness backs up {}, not the answer {}",
+            verification.answer, answer
+        ),
+        Ok(()) => println!("  · Verified: {}", verification.answer),
+    }
 }
 
-fn get_day(day: u8) -> Box<dyn Day> {
-    match day {
+fn read_input(day: u8) -> Option<String> {
+    fs::read_to_string(format!("inputs/{:02}.txt", day)).ok()
+}
+
+fn get_day(day: u8) -> Option<Box<dyn Day>> {
+    let day: Box<dyn Day> = match day {
         1 => Box::new(day01::Day01),
         2 => Box::new(day02::Day02),
         3 => Box::new(day03::Day03),
@@ -53,6 +141,8 @@ fn get_day(day: u8) -> Box<dyn Day> {
         9 => Box::new(day09::Day09),
         10 => Box::new(day10::Day10),
         11 => Box::new(day11::Day11),
-        _ => unimplemented!(),
-    }
+        12 => Box::new(day12::Day12),
+        _ => return None,
+    };
+    Some(day)
 }
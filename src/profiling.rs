@@ -0,0 +1,164 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::Display;
+
+/// Wraps the system allocator and counts allocations made on threads that
+/// currently have profiling enabled. Counters are thread-local so parallel
+/// tests or other threads do not pollute a measurement.
+pub struct CountingAllocator;
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+    static BYTES_ALLOCATED: Cell<u64> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<i64> = const { Cell::new(0) };
+    static PEAK_LIVE_BYTES: Cell<i64> = const { Cell::new(0) };
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct AllocationStats {
+    pub allocations: u64,
+    pub bytes_allocated: u64,
+    pub peak_live_bytes: u64,
+}
+
+impl Display for AllocationStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes_allocated),
+            format_bytes(self.peak_live_bytes)
+        )
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn is_enabled() -> bool {
+    // `try_with` fails during thread teardown, never count in that case
+    ENABLED.try_with(Cell::get).unwrap_or(false)
+}
+
+fn record_allocation(size: usize) {
+    ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+    BYTES_ALLOCATED.set(BYTES_ALLOCATED.get() + size as u64);
+    record_live_change(size as i64);
+}
+
+fn record_live_change(delta: i64) {
+    let live = LIVE_BYTES.get() + delta;
+    LIVE_BYTES.set(live);
+    if live > PEAK_LIVE_BYTES.get() {
+        PEAK_LIVE_BYTES.set(live);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() && is_enabled() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() && is_enabled() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        if is_enabled() {
+            record_live_change(-(layout.size() as i64));
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() && is_enabled() {
+            // A reallocation is counted as a fresh allocation of the new size
+            ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+            BYTES_ALLOCATED.set(BYTES_ALLOCATED.get() + new_size as u64);
+            record_live_change(new_size as i64 - layout.size() as i64);
+        }
+        new_ptr
+    }
+}
+
+/// Runs `f` with allocation counting enabled on the current thread.
+///
+/// Memory freed inside `f` that was allocated before the call lowers the
+/// live byte count below zero, so the peak is relative to the live heap at
+/// the start of the measurement.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocationStats) {
+    ALLOCATIONS.set(0);
+    BYTES_ALLOCATED.set(0);
+    LIVE_BYTES.set(0);
+    PEAK_LIVE_BYTES.set(0);
+
+    ENABLED.set(true);
+    let result = f();
+    ENABLED.set(false);
+
+    let stats = AllocationStats {
+        allocations: ALLOCATIONS.get(),
+        bytes_allocated: BYTES_ALLOCATED.get(),
+        peak_live_bytes: PEAK_LIVE_BYTES.get().max(0) as u64,
+    };
+    (result, stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_measure_counts_single_allocation() {
+        let (_, stats) = measure(|| std::hint::black_box(Box::new([0_u8; 64])));
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.bytes_allocated, 64);
+        assert_eq!(stats.peak_live_bytes, 64);
+    }
+
+    #[test]
+    fn test_measure_peak_after_free() {
+        let (_, stats) = measure(|| {
+            let first = std::hint::black_box(vec![0_u8; 100]);
+            drop(first);
+            let second = std::hint::black_box(vec![0_u8; 40]);
+            drop(second);
+        });
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.bytes_allocated, 140);
+        assert_eq!(stats.peak_live_bytes, 100);
+    }
+
+    #[test]
+    fn test_no_counting_outside_measure() {
+        let _ = measure(|| ());
+        let _unmeasured = std::hint::black_box(vec![0_u8; 1000]);
+        assert_eq!(ALLOCATIONS.get(), 0);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(2048), "2.0 KiB");
+        assert_eq!(format_bytes(3 * 1_048_576), "3.0 MiB");
+    }
+}
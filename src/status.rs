@@ -0,0 +1,306 @@
+use std::fs;
+use std::io::IsTerminal;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+use crate::days::day::Verification;
+
+const DAYS_PER_ROW: u8 = 4;
+const CELL_WIDTH: usize = 18;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PartState {
+    /// Matches the accepted answer and the witness, if any, checks out
+    Verified,
+    /// Produces an answer, but none has been accepted yet
+    Solved,
+    /// Differs from the accepted answer or its witness was rejected
+    Changed,
+    /// Still `todo!()`
+    Todo,
+    /// Panics for any other reason
+    Failed,
+    /// No input file for this day
+    NoInput,
+    /// No module for this day
+    Missing,
+}
+
+impl PartState {
+    fn symbol(&self) -> &'static str {
+        match self {
+            PartState::Verified => "★",
+            PartState::Solved => "☆",
+            PartState::Changed => "!",
+            PartState::Todo => "…",
+            PartState::Failed => "✗",
+            PartState::NoInput => "?",
+            PartState::Missing => "·",
+        }
+    }
+
+    fn colour(&self) -> &'static str {
+        match self {
+            PartState::Verified => "\x1b[1;33m",
+            PartState::Solved => "\x1b[32m",
+            PartState::Changed | PartState::Failed => "\x1b[1;31m",
+            PartState::Todo => "\x1b[36m",
+            PartState::NoInput | PartState::Missing => "\x1b[2m",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            PartState::Verified => "verified",
+            PartState::Solved => "solved",
+            PartState::Changed => "answer changed",
+            PartState::Todo => "todo!",
+            PartState::Failed => "panicked",
+            PartState::NoInput => "no input",
+            PartState::Missing => "missing module",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PartStatus {
+    pub state: PartState,
+    pub runtime_ms: Option<f64>,
+}
+
+/// Determines the state of a part from its answer (or panic message), the accepted answer
+/// and the outcome of its witness check.
+pub fn classify(
+    answer: &Result<String, String>,
+    accepted: Option<&str>,
+    witness: Option<&Result<(), String>>,
+) -> PartState {
+    let answer = match answer {
+        Ok(answer) => answer,
+        Err(message) if message.contains("not yet implemented") => return PartState::Todo,
+        Err(_) => return PartState::Failed,
+    };
+    if witness.is_some_and(|outcome| outcome.is_err()) {
+        return PartState::Changed;
+    }
+    match accepted {
+        Some(accepted) if accepted == answer => PartState::Verified,
+        Some(_) => PartState::Changed,
+        None => PartState::Solved,
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn evaluate_part(
+    solve: impl FnOnce() -> String,
+    verify: impl FnOnce() -> Option<Verification>,
+    accepted: Option<&str>,
+) -> PartStatus {
+    let time = Instant::now();
+    let answer = panic::catch_unwind(AssertUnwindSafe(solve)).map_err(panic_message);
+    let runtime_ms = time.elapsed().as_secs_f64() * 1000.0;
+
+    let verification = match answer {
+        Ok(_) => panic::catch_unwind(AssertUnwindSafe(verify)).unwrap_or(None),
+        Err(_) => None,
+    };
+    let witness = verification
+        .as_ref()
+        .map(|verification| &verification.outcome);
+    PartStatus {
+        state: classify(&answer, accepted, witness),
+        runtime_ms: answer.is_ok().then_some(runtime_ms),
+    }
+}
+
+fn answers_path(day: u8) -> String {
+    format!("answers/{:02}.txt", day)
+}
+
+/// Accepted answers are stored one per line, part one first.
+fn read_accepted_answers(day: u8) -> [Option<String>; 2] {
+    let content = fs::read_to_string(answers_path(day)).unwrap_or_default();
+    let mut lines = content.lines().map(|line| line.trim().to_string());
+    let mut next = || lines.next().filter(|line| !line.is_empty());
+    [next(), next()]
+}
+
+fn day_status(day_number: u8) -> [PartStatus; 2] {
+    let unavailable = |state| {
+        [PartStatus {
+            state,
+            runtime_ms: None,
+        }; 2]
+    };
+    let Some(day) = crate::get_day(day_number) else {
+        return unavailable(PartState::Missing);
+    };
+    let Some(input) = crate::read_input(day_number) else {
+        return unavailable(PartState::NoInput);
+    };
+    let [accepted_one, accepted_two] = read_accepted_answers(day_number);
+
+    [
+        evaluate_part(
+            || day.solve_part_one(&input),
+            || day.verify_part_one(&input),
+            accepted_one.as_deref(),
+        ),
+        evaluate_part(
+            || day.solve_part_two(&input),
+            || day.verify_part_two(&input),
+            accepted_two.as_deref(),
+        ),
+    ]
+}
+
+fn render_part(part: u8, status: &PartStatus, colour: bool) -> String {
+    let runtime = match status.runtime_ms {
+        Some(ms) if ms >= 1000.0 => format!("{:.1} s", ms / 1000.0),
+        Some(ms) => format!("{:.2} ms", ms),
+        None => "-".to_string(),
+    };
+    let text = format!("{} {} {:>14}", part, status.state.symbol(), runtime);
+    if colour {
+        format!("{}{}\x1b[0m", status.state.colour(), text)
+    } else {
+        text
+    }
+}
+
+/// Renders the calendar as rows of day cells, three lines per cell.
+pub fn render_calendar(statuses: &[[PartStatus; 2]], colour: bool) -> String {
+    let mut output = String::new();
+    for (row_idx, row) in statuses.chunks(DAYS_PER_ROW as usize).enumerate() {
+        let first_day = row_idx * DAYS_PER_ROW as usize + 1;
+        let mut lines = [String::new(), String::new(), String::new()];
+        for (offset, parts) in row.iter().enumerate() {
+            let separator = if offset == 0 { "" } else { " │ " };
+            let title = format!("Day {:02}", first_day + offset);
+            lines[0] += &format!("{}{:<width$}", separator, title, width = CELL_WIDTH);
+            lines[1] += separator;
+            lines[1] += &render_part(1, &parts[0], colour);
+            lines[2] += separator;
+            lines[2] += &render_part(2, &parts[1], colour);
+        }
+        for line in lines {
+            output += line.trim_end();
+            output += "\n";
+        }
+        output += "\n";
+    }
+    output
+}
+
+fn render_legend(colour: bool) -> String {
+    let states = [
+        PartState::Verified,
+        PartState::Solved,
+        PartState::Changed,
+        PartState::Todo,
+        PartState::Failed,
+        PartState::NoInput,
+        PartState::Missing,
+    ];
+    states
+        .iter()
+        .map(|state| {
+            let entry = format!("{} {}", state.symbol(), state.description());
+            if colour {
+                format!("{}{}\x1b[0m", state.colour(), entry)
+            } else {
+                entry
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// `status`
+pub fn status_command() {
+    let colour = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+
+    // Parts that are still todo!() panic, keep their messages out of the calendar
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let statuses: Vec<[PartStatus; 2]> = (1..=crate::DAY_COUNT).map(day_status).collect();
+    panic::set_hook(default_hook);
+
+    println!("\n=== Advent of Code 2025 ===\n");
+    print!("{}", render_calendar(&statuses, colour));
+    println!("{}", render_legend(colour));
+}
+
+/// `accept <day>` records the current answers of a day as accepted.
+pub fn accept_command(args: &[String]) {
+    let day_number: u8 = args
+        .first()
+        .and_then(|day| day.parse().ok())
+        .expect("Please provide the day to accept");
+    let day = crate::get_day(day_number).expect("Day not implemented");
+    let input = crate::read_input(day_number).expect("Couldn't read file");
+
+    let answers = [day.solve_part_one(&input), day.solve_part_two(&input)];
+    fs::create_dir_all("answers").expect("Couldn't create answers directory");
+    fs::write(answers_path(day_number), answers.join("\n") + "\n")
+        .expect("Couldn't write accepted answers");
+    println!("Accepted day {:02}: {}", day_number, answers.join(", "));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let answer: Result<String, String> = Ok("42".to_string());
+        assert_eq!(classify(&answer, Some("42"), None), PartState::Verified);
+        assert_eq!(classify(&answer, Some("41"), None), PartState::Changed);
+        assert_eq!(classify(&answer, None, Some(&Ok(()))), PartState::Solved);
+        assert_eq!(
+            classify(&answer, Some("42"), Some(&Err("bad".to_string()))),
+            PartState::Changed
+        );
+    }
+
+    #[test]
+    fn test_classify_panics() {
+        let todo = Err("not yet implemented".to_string());
+        let failed = Err("index out of bounds".to_string());
+        assert_eq!(classify(&todo, Some("1"), None), PartState::Todo);
+        assert_eq!(classify(&failed, None, None), PartState::Failed);
+    }
+
+    #[test]
+    fn test_evaluate_part_catches_todo() {
+        let status = evaluate_part(|| todo!(), || None, None);
+        assert_eq!(status.state, PartState::Todo);
+        assert_eq!(status.runtime_ms, None);
+    }
+
+    #[test]
+    fn test_render_calendar_without_colour() {
+        let verified = PartStatus {
+            state: PartState::Verified,
+            runtime_ms: Some(1.5),
+        };
+        let missing = PartStatus {
+            state: PartState::Missing,
+            runtime_ms: None,
+        };
+        let calendar = render_calendar(&[[verified, verified], [missing, missing]], false);
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert_eq!(lines[0], "Day 01             │ Day 02");
+        assert_eq!(lines[1], "1 ★        1.50 ms │ 1 ·              -");
+        assert_eq!(lines[2], "2 ★        1.50 ms │ 2 ·              -");
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+use crate::utils::parsing::{Field, ParseError};
+
+/// Integer types usable as point coordinates.
+pub trait Coordinate:
+    Copy
+    + Ord
+    + Default
+    + Debug
+    + Display
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+{
+    /// Absolute difference, which never underflows for unsigned coordinates.
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+impl Coordinate for i32 {}
+impl Coordinate for i64 {}
+impl Coordinate for isize {}
+impl Coordinate for u32 {}
+impl Coordinate for u64 {}
+impl Coordinate for usize {}
+
+/// Component-wise operations shared by all point types, used by `BoundingBox`.
+pub trait ComponentWise: Copy {
+    fn component_min(self, other: Self) -> Self;
+    fn component_max(self, other: Self) -> Self;
+    /// Whether every component of `self` is less than or equal to that of `other`.
+    fn component_le(self, other: Self) -> bool;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coordinate> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+
+    #[allow(dead_code, reason = "no 2D day measures taxicab distance yet")]
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    #[allow(dead_code, reason = "no 2D day measures king moves yet")]
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    #[allow(dead_code, reason = "only day 8 measures straight lines, in 3D")]
+    pub fn euclidean_squared(&self, other: &Self) -> T {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        dx * dx + dy * dy
+    }
+}
+
+impl<T: Coordinate> ComponentWise for Point2<T> {
+    fn component_min(self, other: Self) -> Self {
+        Point2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    fn component_max(self, other: Self) -> Self {
+        Point2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    fn component_le(self, other: Self) -> bool {
+        self.x <= other.x && self.y <= other.y
+    }
+}
+
+impl<T: Coordinate> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coordinate> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: PartialEq> PartialEq<(T, T)> for Point2<T> {
+    fn eq(&self, other: &(T, T)) -> bool {
+        self.x == other.0 && self.y == other.1
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+impl<T: Coordinate> FromStr for Point2<T> {
+    type Err = ParseError;
+
+    /// Parses `x,y`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = Field::new(s).tuple(',')?;
+        Ok(Point2 { x, y })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coordinate> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    #[allow(dead_code, reason = "day 8 ranks pairs by straight-line distance only")]
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    #[allow(dead_code, reason = "day 8 ranks pairs by straight-line distance only")]
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    pub fn euclidean_squared(&self, other: &Self) -> T {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        let dz = self.z.abs_diff(other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+}
+
+impl<T: Coordinate> ComponentWise for Point3<T> {
+    fn component_min(self, other: Self) -> Self {
+        Point3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    fn component_max(self, other: Self) -> Self {
+        Point3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    fn component_le(self, other: Self) -> bool {
+        self.x <= other.x && self.y <= other.y && self.z <= other.z
+    }
+}
+
+impl<T: Coordinate> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Coordinate> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: PartialEq> PartialEq<(T, T, T)> for Point3<T> {
+    fn eq(&self, other: &(T, T, T)) -> bool {
+        self.x == other.0 && self.y == other.1 && self.z == other.2
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.x, self.y, self.z)
+    }
+}
+
+impl<T: Coordinate> FromStr for Point3<T> {
+    type Err = ParseError;
+
+    /// Parses `x,y,z`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = Field::new(s).tuple(',')?;
+        Ok(Point3 { x, y, z })
+    }
+}
+
+/// The smallest axis-aligned box containing a set of points, both corners inclusive.
+#[allow(dead_code, reason = "no day needs the extent of a point set yet")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox<P> {
+    pub min: P,
+    pub max: P,
+}
+
+#[allow(dead_code, reason = "unused along with `BoundingBox` itself")]
+impl<P: ComponentWise> BoundingBox<P> {
+    pub fn from_points(points: impl IntoIterator<Item = P>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = BoundingBox {
+            min: first,
+            max: first,
+        };
+        for point in points {
+            bounds.include(point);
+        }
+        Some(bounds)
+    }
+
+    pub fn include(&mut self, point: P) {
+        self.min = self.min.component_min(point);
+        self.max = self.max.component_max(point);
+    }
+
+    pub fn contains(&self, point: P) -> bool {
+        self.min.component_le(point) && point.component_le(self.max)
+    }
+}
+
+#[allow(dead_code, reason = "unused along with `BoundingBox` itself")]
+impl<T: Coordinate> BoundingBox<Point2<T>> {
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_distances_2d() {
+        let a = Point2::new(1_u64, 7);
+        let b = Point2::new(4_u64, 3);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean_squared(&b), 25);
+    }
+
+    #[test]
+    fn test_distances_3d() {
+        let a = Point3::new(162_u64, 817, 812);
+        let b = Point3::new(425_u64, 690, 689);
+        assert_eq!(a.euclidean_squared(&b), 263 * 263 + 127 * 127 + 123 * 123);
+        assert_eq!(a.manhattan(&b), 263 + 127 + 123);
+        assert_eq!(a.chebyshev(&b), 263);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(1_i32, -2);
+        let b = Point2::new(-3_i32, 5);
+        assert_eq!(a + b, (-2, 3));
+        assert_eq!(a - b, (4, -7));
+        assert_eq!(a * 3, (3, -6));
+        assert_eq!(Point3::new(1, 2, 3) + Point3::new(1, 1, 1), (2, 3, 4));
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!("7,1".parse::<Point2<usize>>(), Ok(Point2::new(7, 1)));
+        assert_eq!("-7, 1".parse::<Point2<i64>>(), Ok(Point2::new(-7, 1)));
+        assert_eq!(
+            "57,618,57".parse::<Point3<u64>>(),
+            Ok(Point3::new(57, 618, 57))
+        );
+        let error = "1,2".parse::<Point3<u64>>().unwrap_err();
+        assert_eq!(error.message, "Expected 3 values, found 2");
+        let error = "1,x".parse::<Point2<u64>>().unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "x"));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let points = [(7, 1), (11, 7), (2, 3)].map(Point2::<usize>::from);
+        let bounds = BoundingBox::from_points(points).unwrap();
+        assert_eq!(bounds.min, (2, 1));
+        assert_eq!(bounds.max, (11, 7));
+        assert_eq!((bounds.width(), bounds.height()), (9, 6));
+        assert!(bounds.contains(Point2::new(5, 5)));
+        assert!(!bounds.contains(Point2::new(12, 5)));
+        assert_eq!(BoundingBox::<Point3<u64>>::from_points([]), None);
+    }
+}
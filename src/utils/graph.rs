@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+pub type NodeId = usize;
+
+/// A directed graph over named nodes. Names are interned into dense `NodeId`s and every
+/// node, including one that only appears as a target, has a (possibly empty) edge list.
+#[derive(Debug, Default, Clone)]
+pub struct Graph<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, NodeId>,
+    edges: Vec<Vec<NodeId>>,
+}
+
+/// A cycle found while ordering a graph, as the names along it with the first repeated last.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle<'a>(pub Vec<&'a str>);
+
+impl Display for Cycle<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cycle {}", self.0.join(" -> "))
+    }
+}
+
+impl<'a> Graph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `name`, adding it as a node without edges if it is new.
+    pub fn node(&mut self, name: &'a str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name);
+        self.ids.insert(name, id);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &'a str, to: &'a str) {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.edges[from].push(to);
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    #[allow(dead_code, reason = "day 11 only reports names through `Cycle`")]
+    pub fn name(&self, id: NodeId) -> &'a str {
+        self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[allow(dead_code, reason = "pairs with `len`, which sizes the path tables")]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn successors(&self, id: NodeId) -> &[NodeId] {
+        &self.edges[id]
+    }
+
+    /// Orders the nodes so that every edge points forward, or returns a cycle.
+    pub fn topological_order(&self) -> Result<Vec<NodeId>, Cycle<'a>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            Active,
+            Done,
+        }
+        let mut marks = vec![Mark::New; self.len()];
+        let mut post_order = Vec::with_capacity(self.len());
+
+        for root in 0..self.len() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+            // Each entry is a node and the index of the next successor to visit
+            let mut stack = vec![(root, 0)];
+            marks[root] = Mark::Active;
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                let Some(&successor) = self.edges[node].get(*next) else {
+                    marks[node] = Mark::Done;
+                    post_order.push(node);
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                match marks[successor] {
+                    Mark::New => {
+                        marks[successor] = Mark::Active;
+                        stack.push((successor, 0));
+                    }
+                    Mark::Active => {
+                        let start = stack.iter().position(|(id, _)| *id == successor).unwrap();
+                        let mut cycle: Vec<&str> = stack[start..]
+                            .iter()
+                            .map(|(id, _)| self.names[*id])
+                            .collect();
+                        cycle.push(self.names[successor]);
+                        return Err(Cycle(cycle));
+                    }
+                    Mark::Done => {}
+                }
+            }
+        }
+        post_order.reverse();
+        Ok(post_order)
+    }
+}
+
+impl<'a> FromIterator<(&'a str, Vec<&'a str>)> for Graph<'a> {
+    fn from_iter<I: IntoIterator<Item = (&'a str, Vec<&'a str>)>>(iter: I) -> Self {
+        let mut graph = Graph::new();
+        for (from, targets) in iter {
+            graph.node(from);
+            for to in targets {
+                graph.add_edge(from, to);
+            }
+        }
+        graph
+    }
+}
+
+/// Whether required waypoints may be visited in any order or only in the given one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaypointOrder {
+    Any,
+    #[allow(dead_code, reason = "day 11 lets `dac` and `fft` come in either order")]
+    Fixed,
+}
+
+/// Counts paths in an acyclic graph. The counts from every node to a target are computed
+/// together on the first query for that target and reused by later queries.
+pub struct PathCounter<'g, 'a> {
+    graph: &'g Graph<'a>,
+    order: Vec<NodeId>,
+    paths_to: HashMap<NodeId, Vec<u64>>,
+}
+
+impl<'g, 'a> PathCounter<'g, 'a> {
+    pub fn new(graph: &'g Graph<'a>) -> Result<Self, Cycle<'a>> {
+        Ok(PathCounter {
+            graph,
+            order: graph.topological_order()?,
+            paths_to: HashMap::new(),
+        })
+    }
+
+    /// Number of distinct paths from `from` to `to`, a node has one path to itself.
+    pub fn count(&mut self, from: NodeId, to: NodeId) -> u64 {
+        let graph = self.graph;
+        let order = &self.order;
+        let paths = self.paths_to.entry(to).or_insert_with(|| {
+            let mut paths = vec![0; graph.len()];
+            for &node in order.iter().rev() {
+                paths[node] = if node == to {
+                    1
+                } else {
+                    graph.successors(node).iter().map(|&next| paths[next]).sum()
+                };
+            }
+            paths
+        });
+        paths[from]
+    }
+
+    /// Like `count`, with unknown names having no paths.
+    pub fn count_between(&mut self, from: &str, to: &str) -> u64 {
+        match (self.graph.id(from), self.graph.id(to)) {
+            (Some(from), Some(to)) => self.count(from, to),
+            _ => 0,
+        }
+    }
+
+    /// Number of paths from `from` to `to` that visit every node of `required`, in the
+    /// given `order`, and avoid every node of `forbidden`.
+    pub fn count_via(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        required: &[NodeId],
+        order: WaypointOrder,
+        forbidden: &[NodeId],
+    ) -> u64 {
+        // The state of a partial path is the set of waypoints seen so far for any order,
+        // and the number of waypoints seen so far for a fixed order
+        let (state_count, complete) = match order {
+            WaypointOrder::Any => {
+                assert!(
+                    required.len() <= 16,
+                    "Too many waypoints to visit in any order"
+                );
+                (1 << required.len(), (1 << required.len()) - 1)
+            }
+            WaypointOrder::Fixed => (required.len() + 1, required.len()),
+        };
+        let enter = |node: NodeId, state: usize| -> Option<usize> {
+            if forbidden.contains(&node) {
+                return None;
+            }
+            let Some(idx) = required.iter().position(|&waypoint| waypoint == node) else {
+                return Some(state);
+            };
+            match order {
+                WaypointOrder::Any => Some(state | 1 << idx),
+                WaypointOrder::Fixed => (state == idx).then_some(state + 1),
+            }
+        };
+
+        let mut paths = vec![vec![0_u64; state_count]; self.graph.len()];
+        let Some(initial) = enter(from, 0) else {
+            return 0;
+        };
+        paths[from][initial] = 1;
+        for &node in &self.order {
+            if node == to {
+                continue;
+            }
+            for state in 0..state_count {
+                let count = paths[node][state];
+                if count == 0 {
+                    continue;
+                }
+                for &next in self.graph.successors(node) {
+                    if let Some(next_state) = enter(next, state) {
+                        paths[next][next_state] += count;
+                    }
+                }
+            }
+        }
+        paths[to][complete]
+    }
+
+    /// Like `count_via`, with unknown names as required nodes leaving no paths.
+    pub fn count_via_names(
+        &self,
+        from: &str,
+        to: &str,
+        required: &[&str],
+        order: WaypointOrder,
+        forbidden: &[&str],
+    ) -> u64 {
+        let graph = self.graph;
+        let (Some(from), Some(to)) = (graph.id(from), graph.id(to)) else {
+            return 0;
+        };
+        let Some(required) = required
+            .iter()
+            .map(|name| graph.id(name))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return 0;
+        };
+        let forbidden: Vec<NodeId> = forbidden.iter().filter_map(|name| graph.id(name)).collect();
+        self.count_via(from, to, &required, order, &forbidden)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diamond() -> Graph<'static> {
+        [
+            ("a", vec!["b", "c"]),
+            ("b", vec!["d"]),
+            ("c", vec!["d", "e"]),
+            ("d", vec!["e"]),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_sinks_are_nodes() {
+        let graph = diamond();
+        assert_eq!(graph.len(), 5);
+        let sink = graph.id("e").unwrap();
+        assert_eq!(graph.name(sink), "e");
+        assert!(graph.successors(sink).is_empty());
+        assert_eq!(graph.id("f"), None);
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = diamond();
+        let order = graph.topological_order().unwrap();
+        let mut position = vec![0; graph.len()];
+        for (idx, &node) in order.iter().enumerate() {
+            position[node] = idx;
+        }
+        for node in 0..graph.len() {
+            for &next in graph.successors(node) {
+                assert!(position[node] < position[next]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cycle_is_reported() {
+        let graph: Graph = [("a", vec!["b"]), ("b", vec!["c"]), ("c", vec!["d", "b"])]
+            .into_iter()
+            .collect();
+        assert_eq!(graph.topological_order(), Err(Cycle(vec!["b", "c", "b"])));
+        assert!(PathCounter::new(&graph).is_err());
+    }
+
+    #[test]
+    fn test_path_counting() {
+        let graph = diamond();
+        let mut counter = PathCounter::new(&graph).unwrap();
+        assert_eq!(counter.count_between("a", "e"), 3);
+        assert_eq!(counter.count_between("c", "e"), 2);
+        assert_eq!(counter.count_between("a", "d"), 2);
+        assert_eq!(counter.count_between("e", "a"), 0);
+        assert_eq!(counter.count_between("d", "d"), 1);
+        assert_eq!(counter.count_between("a", "missing"), 0);
+    }
+
+    #[test]
+    fn test_path_counting_via_waypoints() {
+        let graph = diamond();
+        let counter = PathCounter::new(&graph).unwrap();
+        let count = |required: &[&str], order, forbidden: &[&str]| {
+            counter.count_via_names("a", "e", required, order, forbidden)
+        };
+        assert_eq!(count(&[], WaypointOrder::Any, &[]), 3);
+        assert_eq!(count(&["d"], WaypointOrder::Any, &[]), 2);
+        assert_eq!(count(&["d", "c"], WaypointOrder::Any, &[]), 1);
+        assert_eq!(count(&["c", "d"], WaypointOrder::Fixed, &[]), 1);
+        assert_eq!(count(&["d", "c"], WaypointOrder::Fixed, &[]), 0);
+        assert_eq!(count(&["d"], WaypointOrder::Any, &["b"]), 1);
+        assert_eq!(count(&["b", "c"], WaypointOrder::Any, &[]), 0);
+        assert_eq!(count(&["a", "e"], WaypointOrder::Fixed, &[]), 3);
+        assert_eq!(count(&[], WaypointOrder::Any, &["e"]), 0);
+        assert_eq!(count(&["missing"], WaypointOrder::Any, &[]), 0);
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the four orthogonal neighbours.
+pub const DIRS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets of all eight neighbours, orthogonal and diagonal.
+pub const DIRS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A dense, row-major 2D grid indexed by `(x, y)` with `(0, 0)` in the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            cells.len(),
+            "Grid of {}x{} needs {} cells",
+            width,
+            height,
+            width * height
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parses a character map, converting every character with `convert`.
+    /// Panics if the lines are not all of the same length.
+    pub fn parse_with(input: &str, convert: impl Fn(char) -> T) -> Self {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in input.lines().enumerate() {
+            let row_start = cells.len();
+            cells.extend(line.chars().map(&convert));
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    panic!("Line {} has width {}, expected {}", y + 1, row_width, width)
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        Grid::new(width.unwrap_or(0), height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// In-bounds positions reached from `(x, y)` by the given offsets.
+    pub fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            self.in_bounds(nx, ny).then_some((nx as usize, ny as usize))
+        })
+    }
+
+    /// In-bounds orthogonal neighbours of `(x, y)`.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &DIRS_4)
+    }
+
+    /// In-bounds orthogonal and diagonal neighbours of `(x, y)`.
+    #[allow(dead_code, reason = "day 4 counts diagonals via `ThresholdRule`")]
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &DIRS_8)
+    }
+
+    #[allow(dead_code, reason = "days read whole maps through `rows`")]
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "Column {} out of bounds", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// All cells in row-major order together with their position.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| ((i % width, i / width), value))
+    }
+
+    /// Positions of all cells matching `predicate`, in row-major order.
+    pub fn positions(&self, predicate: impl Fn(&T) -> bool) -> Vec<(usize, usize)> {
+        self.iter()
+            .filter(|(_, value)| predicate(value))
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Mirrors the grid along its main diagonal, rows become columns.
+    #[allow(dead_code, reason = "day 12 flips its shapes as cell offsets instead")]
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid::new(self.height, self.width, cells)
+    }
+
+    /// Rotates the grid by 90° clockwise.
+    #[allow(dead_code, reason = "day 12 turns its shapes as cell offsets instead")]
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).rev().cloned().collect::<Vec<_>>())
+            .collect();
+        Grid::new(self.height, self.width, cells)
+    }
+
+    /// Rotates the grid by 90° counter-clockwise.
+    #[allow(dead_code, reason = "day 12 turns its shapes as cell offsets instead")]
+    pub fn rotate_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid::new(self.height, self.width, cells)
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Self {
+        Grid::parse_with(input, |c| c)
+    }
+
+    /// Position of the first occurrence of `target` in row-major order.
+    pub fn find(&self, target: char) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, value)| **value == target)
+            .map(|(position, _)| position)
+    }
+
+    pub fn find_all(&self, target: char) -> Vec<(usize, usize)> {
+        self.positions(|value| *value == target)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside the grid", x, y))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside the grid", x, y))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const EXAMPLE_INPUT: &str = "ab.
+.c#";
+
+    #[test]
+    fn test_parsing_and_display_roundtrip() {
+        let grid = Grid::parse(EXAMPLE_INPUT);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(1, 1)], 'c');
+        assert_eq!(grid.to_string(), EXAMPLE_INPUT);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 2 has width 2, expected 3")]
+    fn test_parsing_ragged_lines() {
+        Grid::parse("abc\nab");
+    }
+
+    #[test]
+    fn test_neighbours_are_bounds_checked() {
+        let grid = Grid::parse(EXAMPLE_INPUT);
+        let corner: Vec<_> = grid.neighbours4(0, 0).collect();
+        assert_eq!(corner, [(1, 0), (0, 1)]);
+        let centre: Vec<_> = grid.neighbours8(1, 0).collect();
+        assert_eq!(centre, [(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_row_and_column_views() {
+        let grid = Grid::parse(EXAMPLE_INPUT);
+        assert_eq!(grid.row(1), ['.', 'c', '#']);
+        assert_eq!(grid.column(1).collect::<String>(), "bc");
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Grid::parse(EXAMPLE_INPUT);
+        assert_eq!(grid.find('#'), Some((2, 1)));
+        assert_eq!(grid.find('x'), None);
+        assert_eq!(grid.find_all('.'), [(2, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_transpose_and_rotation() {
+        let grid = Grid::parse(EXAMPLE_INPUT);
+        assert_eq!(grid.transpose().to_string(), "a.\nbc\n.#");
+        assert_eq!(grid.rotate_clockwise().to_string(), ".a\ncb\n#.");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), ".#\nbc\na.");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_counter_clockwise(),
+            grid.clone()
+        );
+    }
+}
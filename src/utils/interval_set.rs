@@ -0,0 +1,244 @@
+use std::cmp;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::utils::parsing::{self, ParseError};
+
+/// Integer types with a successor and predecessor, usable as interval bounds.
+pub trait Discrete: Copy + Ord + Debug {
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+    /// Number of values in `start..=end`, saturating at `u64::MAX`.
+    fn count(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),+) => {
+        $(impl Discrete for $t {
+            fn succ(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn pred(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn count(start: Self, end: Self) -> u64 {
+                (end as i128 - start as i128 + 1).clamp(0, u64::MAX as i128) as u64
+            }
+        })+
+    };
+}
+
+impl_discrete!(i32, i64, u32, u64, usize);
+
+/// A set of values stored as sorted, disjoint and non-adjacent inclusive intervals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all values of `range`, merging it with overlapping and adjacent intervals.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        // First interval that overlaps or touches the new one
+        let first = self
+            .intervals
+            .partition_point(|(_, existing_end)| existing_end.succ().is_some_and(|e| e < start));
+        // One past the last interval that overlaps or touches the new one
+        let last = self
+            .intervals
+            .partition_point(|(existing_start, _)| end.succ().is_none_or(|e| *existing_start <= e));
+        if first < last {
+            start = cmp::min(start, self.intervals[first].0);
+            end = cmp::max(end, self.intervals[last - 1].1);
+        }
+        self.intervals.splice(first..last, [(start, end)]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.intervals.partition_point(|(start, _)| *start <= value);
+        idx > 0 && self.intervals[idx - 1].1 >= value
+    }
+
+    /// Total number of values covered by the set.
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .map(|(start, end)| T::count(*start, *end))
+            .sum()
+    }
+
+    #[allow(dead_code, reason = "the usual partner of `len`, only the tests ask")]
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    #[allow(dead_code, reason = "day 5 counts covered IDs, not intervals")]
+    /// Number of disjoint intervals.
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.iter().map(|(start, end)| *start..=*end)
+    }
+
+    #[allow(dead_code, reason = "day 5 builds its set from one list of ranges")]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.insert(range);
+        }
+        result
+    }
+
+    #[allow(dead_code, reason = "no day combines two sets of ranges yet")]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a_start, a_end) = self.intervals[i];
+            let (b_start, b_end) = other.intervals[j];
+            let start = cmp::max(a_start, b_start);
+            let end = cmp::min(a_end, b_end);
+            if start <= end {
+                intervals.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let mut j = 0;
+        for &(start, end) in &self.intervals {
+            let mut current = Some(start);
+            while j < other.intervals.len() && other.intervals[j].1 < start {
+                j += 1;
+            }
+            let mut k = j;
+            while let Some(from) = current {
+                if k >= other.intervals.len() || other.intervals[k].0 > end {
+                    intervals.push((from, end));
+                    break;
+                }
+                let (cut_start, cut_end) = other.intervals[k];
+                if cut_start > from {
+                    intervals.push((from, cut_start.pred().unwrap()));
+                }
+                current = if cut_end < end { cut_end.succ() } else { None };
+                k += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl<T: Discrete> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Discrete + FromStr> FromStr for IntervalSet<T> {
+    type Err = ParseError;
+
+    /// Parses a list of `a-b` ranges separated by commas and/or line breaks.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parsing::lines(s)
+            .flat_map(|line| line.split(','))
+            .filter(|item| !item.is_empty())
+            .map(|item| item.range())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn intervals(set: &IntervalSet<u64>) -> Vec<RangeInclusive<u64>> {
+        set.iter().collect()
+    }
+
+    fn intervals_i64(set: &IntervalSet<i64>) -> Vec<RangeInclusive<i64>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges_overlapping_and_adjacent() {
+        let set: IntervalSet<u64> = [3..=5, 10..=14, 16..=20, 12..=18, 6..=6]
+            .into_iter()
+            .collect();
+        assert_eq!(intervals(&set), [3..=6, 10..=20]);
+        assert_eq!(set.len(), 15);
+    }
+
+    #[test]
+    fn test_insert_spanning_several_intervals() {
+        let mut set: IntervalSet<u64> = [1..=2, 5..=6, 9..=10, 20..=21].into_iter().collect();
+        set.insert(4..=12);
+        assert_eq!(intervals(&set), [1..=2, 4..=12, 20..=21]);
+        set.insert(0..=u64::MAX);
+        assert_eq!(intervals(&set), [0..=u64::MAX]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: IntervalSet<u64> = [3..=5, 10..=20].into_iter().collect();
+        let contained: Vec<u64> = [1, 5, 8, 11, 17, 32]
+            .into_iter()
+            .filter(|id| set.contains(*id))
+            .collect();
+        assert_eq!(contained, [5, 11, 17]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<u64> = [1..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<u64> = [5..=22, 28..=40].into_iter().collect();
+        assert_eq!(intervals(&a.union(&b)), [1..=40]);
+        assert_eq!(intervals(&a.intersection(&b)), [5..=10, 20..=22, 28..=30]);
+        assert_eq!(intervals(&a.difference(&b)), [1..=4, 23..=27]);
+        assert_eq!(intervals(&b.difference(&a)), [11..=19, 31..=40]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_parsing() {
+        let set: IntervalSet<u64> = "3-5\n10-14,16-20\n12-18".parse().unwrap();
+        assert_eq!(intervals(&set), [3..=5, 10..=20]);
+        let set: IntervalSet<i64> = "-5--2".parse().unwrap();
+        assert_eq!(intervals_i64(&set), [-5..=-2]);
+        let error = "3-5\n10-14,11".parse::<IntervalSet<u64>>().unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 7, "11")
+        );
+    }
+}
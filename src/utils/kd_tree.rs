@@ -0,0 +1,230 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::utils::geometry::Point3;
+
+type Point = Point3<u64>;
+
+fn coordinate(point: &Point, axis: usize) -> u64 {
+    match axis {
+        0 => point.x,
+        1 => point.y,
+        _ => point.z,
+    }
+}
+
+/// A k-d tree over a slice of 3D points, answering queries with indices into that slice.
+/// The tree is stored implicitly: the median of every sub-slice of `nodes` is its root.
+pub struct KdTree<'a> {
+    points: &'a [Point],
+    nodes: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    pub fn new(points: &'a [Point]) -> Self {
+        let mut nodes: Vec<usize> = (0..points.len()).collect();
+        build(points, &mut nodes, 0);
+        KdTree { points, nodes }
+    }
+
+    #[allow(dead_code, reason = "day 8 keeps its own slice of the points")]
+    pub fn points(&self) -> &'a [Point] {
+        self.points
+    }
+
+    /// The `k` points closest to `target` as `(squared distance, index)`, closest first.
+    /// Ties are broken by index.
+    pub fn nearest(&self, target: &Point, k: usize) -> Vec<(u64, usize)> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(0, self.nodes.len(), 0, target, k, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    fn search(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        target: &Point,
+        k: usize,
+        best: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if start >= end {
+            return;
+        }
+        let mid = start + (end - start) / 2;
+        let index = self.nodes[mid];
+        let candidate = (self.points[index].euclidean_squared(target), index);
+        if best.len() < k {
+            best.push(candidate);
+        } else if best.peek().is_some_and(|worst| candidate < *worst) {
+            best.pop();
+            best.push(candidate);
+        }
+
+        let axis = depth % 3;
+        let split = coordinate(&self.points[index], axis);
+        let value = coordinate(target, axis);
+        let (near, far) = if value < split {
+            ((start, mid), (mid + 1, end))
+        } else {
+            ((mid + 1, end), (start, mid))
+        };
+        self.search(near.0, near.1, depth + 1, target, k, best);
+        let plane_distance = value.abs_diff(split).pow(2);
+        if best.len() < k || best.peek().is_some_and(|worst| plane_distance <= worst.0) {
+            self.search(far.0, far.1, depth + 1, target, k, best);
+        }
+    }
+
+    /// Streams every pair of points once as `(squared distance, i, j)` with `i < j`,
+    /// in increasing distance, without materialising all pairs.
+    pub fn pairs_by_distance(&self) -> ClosestPairs<'_, 'a> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbours: vec![vec![]; self.points.len()],
+            cursors: vec![0; self.points.len()],
+            queue: BinaryHeap::new(),
+        };
+        for point in 0..self.points.len() {
+            pairs.advance(point);
+        }
+        pairs
+    }
+}
+
+fn build(points: &[Point], nodes: &mut [usize], depth: usize) {
+    if nodes.len() <= 1 {
+        return;
+    }
+    let mid = nodes.len() / 2;
+    nodes.select_nth_unstable_by_key(mid, |&index| coordinate(&points[index], depth % 3));
+    let (left, right) = nodes.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+/// Iterator returned by `KdTree::pairs_by_distance`. Every point walks its own neighbours
+/// from closest to farthest, fetching them from the tree in growing batches, and the queue
+/// holds the next unvisited neighbour of every point.
+pub struct ClosestPairs<'t, 'a> {
+    tree: &'t KdTree<'a>,
+    neighbours: Vec<Vec<(u64, usize)>>,
+    cursors: Vec<usize>,
+    queue: BinaryHeap<Reverse<(u64, usize, usize)>>,
+}
+
+impl ClosestPairs<'_, '_> {
+    /// Queues the next neighbour of `point`, if it has one left.
+    fn advance(&mut self, point: usize) {
+        let count = self.tree.points.len();
+        let cursor = self.cursors[point];
+        if cursor >= self.neighbours[point].len() {
+            let fetched = self.neighbours[point].len();
+            if fetched >= count - 1 {
+                return;
+            }
+            // Includes the point itself, which is skipped below
+            let batch = (2 * fetched).max(8).min(count);
+            let target = self.tree.points[point];
+            self.neighbours[point] = self
+                .tree
+                .nearest(&target, batch)
+                .into_iter()
+                .filter(|(_, index)| *index != point)
+                .collect();
+        }
+        if let Some(&(distance, other)) = self.neighbours[point].get(cursor) {
+            self.cursors[point] += 1;
+            self.queue.push(Reverse((distance, point, other)));
+        }
+    }
+}
+
+impl Iterator for ClosestPairs<'_, '_> {
+    type Item = (u64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Each pair is reached from both ends, only the walk from the lower index yields it
+        while let Some(Reverse((distance, point, other))) = self.queue.pop() {
+            self.advance(point);
+            if point < other {
+                return Some((distance, point, other));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Deterministic pseudo-random points, with a few duplicates.
+    fn sample_points(count: usize) -> Vec<Point> {
+        let mut state = 0x2545_f491_u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % 1000
+        };
+        let mut points: Vec<Point> = (0..count)
+            .map(|_| Point::new(next(), next(), next()))
+            .collect();
+        points[count - 1] = points[0];
+        points
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let points = sample_points(200);
+        let tree = KdTree::new(&points);
+        for target in [points[3], points[0], Point::new(500, 500, 500)] {
+            let mut expected: Vec<(u64, usize)> = points
+                .iter()
+                .enumerate()
+                .map(|(index, point)| (point.euclidean_squared(&target), index))
+                .collect();
+            expected.sort_unstable();
+            assert_eq!(tree.nearest(&target, 7), expected[..7]);
+        }
+        assert_eq!(tree.nearest(&points[0], 0), []);
+    }
+
+    fn brute_force_pairs(points: &[Point]) -> Vec<(u64, usize, usize)> {
+        let mut expected = vec![];
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                expected.push((points[i].euclidean_squared(&points[j]), i, j));
+            }
+        }
+        expected.sort_unstable();
+        expected
+    }
+
+    #[test]
+    fn test_pairs_by_distance_matches_brute_force() {
+        let points = sample_points(60);
+        let pairs: Vec<_> = KdTree::new(&points).pairs_by_distance().collect();
+        assert_eq!(pairs, brute_force_pairs(&points));
+    }
+
+    #[test]
+    fn test_pairs_by_distance_below_batch_size() {
+        for count in [2, 3, 7] {
+            let points = sample_points(count);
+            let pairs: Vec<_> = KdTree::new(&points).pairs_by_distance().collect();
+            assert_eq!(pairs, brute_force_pairs(&points));
+        }
+    }
+
+    #[test]
+    fn test_tiny_inputs() {
+        assert_eq!(KdTree::new(&[]).pairs_by_distance().next(), None);
+        let single = [Point::new(1, 2, 3)];
+        assert_eq!(KdTree::new(&single).pairs_by_distance().next(), None);
+    }
+}
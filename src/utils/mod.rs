@@ -0,0 +1,13 @@
+// Shared building blocks for the days, not every day uses every helper
+
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod interval_set;
+pub mod kd_tree;
+pub mod mst;
+pub mod neighbourhood;
+pub mod parsing;
+pub mod polygon;
+pub mod repetition;
+pub mod union_find;
@@ -0,0 +1,138 @@
+use crate::utils::union_find::UnionFind;
+
+/// An undirected weighted edge between two vertex indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub weight: u64,
+    pub a: usize,
+    pub b: usize,
+}
+
+impl From<(u64, usize, usize)> for Edge {
+    fn from((weight, a, b): (u64, usize, usize)) -> Self {
+        Edge { weight, a, b }
+    }
+}
+
+/// Kruskal's algorithm, run one candidate edge at a time so callers can stop after any
+/// number of edges or as soon as everything is connected. Candidate edges must come in
+/// non-decreasing weight order, e.g. from `KdTree::pairs_by_distance`.
+pub struct Kruskal<I> {
+    edges: I,
+    components: UnionFind,
+    tree: Vec<Edge>,
+    considered: usize,
+}
+
+impl<I: Iterator<Item = (u64, usize, usize)>> Kruskal<I> {
+    pub fn new(vertex_count: usize, edges: I) -> Self {
+        Kruskal {
+            edges,
+            components: UnionFind::with_len(vertex_count),
+            tree: vec![],
+            considered: 0,
+        }
+    }
+
+    /// Considers the next candidate edge and returns it with whether it joined two
+    /// components, or `None` once the candidates are exhausted.
+    pub fn step(&mut self) -> Option<(Edge, bool)> {
+        let edge: Edge = self.edges.next()?.into();
+        self.considered += 1;
+        let joined = self.components.join(edge.a, edge.b);
+        if joined {
+            self.tree.push(edge);
+        }
+        Some((edge, joined))
+    }
+
+    #[allow(dead_code, reason = "day 8 calls `step` itself to trace each union")]
+    /// Considers up to `count` more candidate edges, the forest is then available through
+    /// `tree` and `components`.
+    pub fn consider(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.step().is_none() {
+                break;
+            }
+        }
+    }
+
+    #[allow(dead_code, reason = "day 8 steps itself and keeps the last union")]
+    /// Adds edges until all vertices are connected and returns the edge that completed
+    /// connectivity, or `None` if the graph is already connected or cannot be connected.
+    pub fn complete(&mut self) -> Option<Edge> {
+        let mut completing = None;
+        while !self.is_connected() {
+            let (edge, joined) = self.step()?;
+            if joined {
+                completing = Some(edge);
+            }
+        }
+        completing
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.components.component_count() <= 1
+    }
+
+    #[allow(dead_code, reason = "day 8 reads the forest through `components`")]
+    /// Edges accepted so far, in the order they were added.
+    pub fn tree(&self) -> &[Edge] {
+        &self.tree
+    }
+
+    pub fn components(&self) -> &UnionFind {
+        &self.components
+    }
+
+    #[allow(dead_code, reason = "only the tests look at how far the search got")]
+    /// Number of candidate edges considered so far, accepted or not.
+    pub fn considered(&self) -> usize {
+        self.considered
+    }
+}
+
+#[allow(dead_code, reason = "day 8 stops before the tree is complete")]
+/// The minimum spanning forest of `vertex_count` vertices over sorted candidate `edges`.
+pub fn minimum_spanning_tree(
+    vertex_count: usize,
+    edges: impl Iterator<Item = (u64, usize, usize)>,
+) -> Vec<Edge> {
+    let mut kruskal = Kruskal::new(vertex_count, edges);
+    kruskal.complete();
+    kruskal.tree
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A square 0-1-2-3 with one diagonal, sorted by weight.
+    const EDGES: [(u64, usize, usize); 5] = [(1, 0, 1), (2, 1, 2), (3, 0, 2), (4, 2, 3), (5, 3, 0)];
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let tree = minimum_spanning_tree(4, EDGES.into_iter());
+        let weights: Vec<u64> = tree.iter().map(|edge| edge.weight).collect();
+        assert_eq!(weights, [1, 2, 4]);
+    }
+
+    #[test]
+    fn test_stepping() {
+        let mut kruskal = Kruskal::new(4, EDGES.into_iter());
+        kruskal.consider(3);
+        assert_eq!(kruskal.tree().len(), 2);
+        assert_eq!(kruskal.considered(), 3);
+        assert_eq!(kruskal.components().largest_components(2), [3, 1]);
+        assert_eq!(kruskal.complete(), Some(Edge::from((4, 2, 3))));
+        assert!(kruskal.is_connected());
+        assert_eq!(kruskal.complete(), None);
+    }
+
+    #[test]
+    fn test_disconnected_graph() {
+        let mut kruskal = Kruskal::new(5, EDGES.into_iter());
+        assert_eq!(kruskal.complete(), None);
+        assert_eq!(kruskal.components().component_count(), 2);
+    }
+}
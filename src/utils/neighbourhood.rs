@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::utils::geometry::Point2;
+use crate::utils::grid::{DIRS_4, DIRS_8};
+
+type Position = Point2<i32>;
+
+const VON_NEUMANN: [Position; 4] = as_points(DIRS_4);
+const MOORE: [Position; 8] = as_points(DIRS_8);
+
+/// The grid's neighbour offsets as points, so that both share one table.
+const fn as_points<const N: usize>(offsets: [(isize, isize); N]) -> [Position; N] {
+    let mut points = [Point2::new(0, 0); N];
+    let mut idx = 0;
+    while idx < N {
+        points[idx] = Point2::new(offsets[idx].0 as i32, offsets[idx].1 as i32);
+        idx += 1;
+    }
+    points
+}
+
+/// The cells around a cell that count as its neighbours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The four orthogonal cells
+    #[allow(dead_code, reason = "day 4 only needs the eight surrounding cells")]
+    VonNeumann,
+    /// All eight surrounding cells
+    Moore,
+    /// Any offsets. Repeated offsets and `(0, 0)` are ignored
+    #[allow(dead_code, reason = "day 4 only needs the eight surrounding cells")]
+    Custom(Vec<Position>),
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> &[Position] {
+        match self {
+            Neighbourhood::VonNeumann => &VON_NEUMANN,
+            Neighbourhood::Moore => &MOORE,
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// Picks out the live cells with fewer than `threshold` live neighbours. On a torus of the
+/// given size, neighbours past an edge wrap around to the opposite one, and every cell has to
+/// lie on it. Every neighbouring cell counts once, even if several offsets wrap onto it, and
+/// a cell never neighbours itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdRule {
+    neighbourhood: Neighbourhood,
+    threshold: u32,
+    torus: Option<Position>,
+}
+
+impl ThresholdRule {
+    pub const fn new(neighbourhood: Neighbourhood, threshold: u32) -> Self {
+        ThresholdRule {
+            neighbourhood,
+            threshold,
+            torus: None,
+        }
+    }
+
+    #[allow(dead_code, reason = "the day 4 floor has hard edges")]
+    pub fn on_torus(self, width: i32, height: i32) -> Self {
+        assert!(width > 0 && height > 0, "A torus needs a positive size");
+        ThresholdRule {
+            torus: Some(Point2::new(width, height)),
+            ..self
+        }
+    }
+
+    pub fn neighbours(&self, cell: Position) -> impl Iterator<Item = Position> + '_ {
+        let offsets = self.neighbourhood.offsets();
+        // The built-in neighbourhoods only collide once they wrap
+        let may_collide =
+            self.torus.is_some() || matches!(self.neighbourhood, Neighbourhood::Custom(_));
+        offsets
+            .iter()
+            .enumerate()
+            .filter(move |&(idx, &offset)| {
+                if !may_collide {
+                    return true;
+                }
+                let is_itself = self.same_cell(offset, Point2::new(0, 0));
+                let is_repeat = offsets[..idx]
+                    .iter()
+                    .any(|&earlier| self.same_cell(earlier, offset));
+                !is_itself && !is_repeat
+            })
+            .map(move |(_, &offset)| self.wrap(cell + offset))
+    }
+
+    fn wrap(&self, cell: Position) -> Position {
+        match self.torus {
+            Some(size) => Point2::new(cell.x.rem_euclid(size.x), cell.y.rem_euclid(size.y)),
+            None => cell,
+        }
+    }
+
+    /// Cells off the torus would be counted under their wrapped position but looked up under
+    /// their own.
+    fn assert_on_torus(&self, cell: &Position) {
+        assert!(
+            self.wrap(*cell) == *cell,
+            "Cell {} lies outside the torus",
+            cell
+        );
+    }
+
+    /// Whether two offsets lead from any cell to the same cell.
+    fn same_cell(&self, a: Position, b: Position) -> bool {
+        self.wrap(a) == self.wrap(b)
+    }
+
+    /// Number of live neighbours of every cell next to a live one.
+    pub fn neighbour_counts(&self, live_cells: &HashSet<Position>) -> HashMap<Position, u32> {
+        let mut cell_counts: HashMap<Position, u32> = HashMap::new();
+        for live in live_cells {
+            self.assert_on_torus(live);
+            for neighbour in self.neighbours(*live) {
+                *cell_counts.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+        cell_counts
+    }
+
+    /// Whether `cell` has fewer than `threshold` live neighbours according to `cell_counts`.
+    pub fn is_below(&self, cell_counts: &HashMap<Position, u32>, cell: &Position) -> bool {
+        self.assert_on_torus(cell);
+        *cell_counts.get(cell).unwrap_or(&0) < self.threshold
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// All cells of a `width` x `height` rectangle.
+    fn block(width: i32, height: i32) -> HashSet<Position> {
+        (0..width)
+            .flat_map(|x| (0..height).map(move |y| Point2::new(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn test_neighbourhoods() {
+        let cells = block(3, 3);
+        let centre = Point2::new(1, 1);
+        let count = |rule: &ThresholdRule| {
+            let counts = rule.neighbour_counts(&cells);
+            counts.get(&centre).copied().unwrap_or(0)
+        };
+        assert_eq!(count(&ThresholdRule::new(Neighbourhood::Moore, 4)), 8);
+        assert_eq!(count(&ThresholdRule::new(Neighbourhood::VonNeumann, 4)), 4);
+        let knight = Neighbourhood::Custom(vec![Point2::new(1, 2), Point2::new(-1, -2)]);
+        assert_eq!(count(&ThresholdRule::new(knight, 1)), 0);
+    }
+
+    #[test]
+    fn test_torus() {
+        let cells = block(3, 3);
+        // On a 3x3 torus every cell sees all the others
+        let rule = ThresholdRule::new(Neighbourhood::Moore, 8).on_torus(3, 3);
+        let counts = rule.neighbour_counts(&cells);
+        assert_eq!(counts.len(), 9);
+        assert!(counts.values().all(|count| *count == 8));
+        assert!(!rule.is_below(&counts, &Point2::new(0, 0)));
+        let wrapped: Vec<Position> = rule.neighbours(Point2::new(0, 0)).collect();
+        assert!(wrapped.contains(&Point2::new(2, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cell (3,1) lies outside the torus")]
+    fn test_cells_off_the_torus() {
+        let rule = ThresholdRule::new(Neighbourhood::Moore, 4).on_torus(3, 3);
+        rule.neighbour_counts(&HashSet::from([Point2::new(3, 1)]));
+    }
+
+    #[test]
+    fn test_repeated_offsets_count_once() {
+        let cells = block(3, 3);
+        let offsets = vec![Point2::new(1, 0), Point2::new(1, 0), Point2::new(0, 0)];
+        let rule = ThresholdRule::new(Neighbourhood::Custom(offsets), 1);
+        assert_eq!(rule.neighbours(Point2::new(0, 0)).count(), 1);
+        assert_eq!(rule.neighbour_counts(&cells)[&Point2::new(1, 1)], 1);
+    }
+
+    #[test]
+    fn test_wrapped_offsets_count_once() {
+        // On a 2x2 torus left and right, up and down and all four diagonals coincide
+        let rule = ThresholdRule::new(Neighbourhood::Moore, 4).on_torus(2, 2);
+        let neighbours: HashSet<Position> = rule.neighbours(Point2::new(0, 0)).collect();
+        assert_eq!(rule.neighbours(Point2::new(0, 0)).count(), 3);
+        assert!(!neighbours.contains(&Point2::new(0, 0)));
+        let counts = rule.neighbour_counts(&block(2, 2));
+        assert!(counts.values().all(|count| *count == 3));
+        // A single column wraps left and right onto the cell itself
+        let column = ThresholdRule::new(Neighbourhood::VonNeumann, 4).on_torus(1, 5);
+        assert_eq!(column.neighbours(Point2::new(0, 2)).count(), 2);
+    }
+}
@@ -0,0 +1,370 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::utils::grid::Grid;
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// A parse failure with the 1-based line and column of the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: {} `{}`",
+            self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Unwraps a parse result, panicking with the positioned message.
+pub fn or_panic<T>(result: ParseResult<T>) -> T {
+    result.unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// A piece of the input that knows where it starts. Every piece derived from it through
+/// its methods keeps an accurate position for error reporting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Field<'a> {
+    /// The whole input, starting at line 1, column 1.
+    pub fn new(text: &'a str) -> Self {
+        Field {
+            line: 1,
+            column: 1,
+            text,
+        }
+    }
+
+    /// Positions `part`, which must be a slice of this field's text.
+    fn sub(&self, part: &'a str) -> Field<'a> {
+        let offset = part.as_ptr() as usize - self.text.as_ptr() as usize;
+        let before = &self.text[..offset];
+        match before.rfind('\n') {
+            None => Field {
+                line: self.line,
+                column: self.column + before.chars().count(),
+                text: part,
+            },
+            Some(last_newline) => Field {
+                line: self.line + before.matches('\n').count(),
+                column: 1 + before[last_newline + 1..].chars().count(),
+                text: part,
+            },
+        }
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            text: self.text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn trim(&self) -> Field<'a> {
+        self.sub(self.text.trim())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    pub fn lines(self) -> impl Iterator<Item = Field<'a>> {
+        self.text.lines().map(move |line| self.sub(line))
+    }
+
+    /// Groups of consecutive non-blank lines, separated by one or more blank lines.
+    pub fn sections(&self) -> Vec<Field<'a>> {
+        let mut sections = vec![];
+        let mut current: Option<(&'a str, &'a str)> = None;
+        for line in self.text.lines() {
+            if line.trim().is_empty() {
+                sections.extend(current.take().map(|(first, last)| self.span(first, last)));
+            } else {
+                current = Some((current.map_or(line, |(first, _)| first), line));
+            }
+        }
+        sections.extend(current.map(|(first, last)| self.span(first, last)));
+        sections
+    }
+
+    /// The text from the start of `first` to the end of `last`, both slices of this field.
+    fn span(&self, first: &'a str, last: &'a str) -> Field<'a> {
+        let start = first.as_ptr() as usize - self.text.as_ptr() as usize;
+        let end = last.as_ptr() as usize - self.text.as_ptr() as usize + last.len();
+        self.sub(&self.text[start..end])
+    }
+
+    pub fn split(self, separator: char) -> impl Iterator<Item = Field<'a>> {
+        self.text.split(separator).map(move |part| self.sub(part))
+    }
+
+    pub fn split_once(&self, separator: char) -> ParseResult<(Field<'a>, Field<'a>)> {
+        let (left, right) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("Expected `{}`", separator)))?;
+        Ok((self.sub(left), self.sub(right)))
+    }
+
+    /// The text between `open` and `close`, which must surround the trimmed field.
+    pub fn delimited(&self, open: char, close: char) -> ParseResult<Field<'a>> {
+        let field = self.trim();
+        field
+            .text
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+            .map(|inner| field.sub(inner))
+            .ok_or_else(|| field.error(format!("Expected `{}...{}`", open, close)))
+    }
+
+    #[allow(dead_code, reason = "every day so far reads integers through `int`")]
+    /// Parses the trimmed text with its `FromStr` implementation.
+    pub fn parse<T: FromStr>(&self) -> ParseResult<T>
+    where
+        T::Err: Display,
+    {
+        let field = self.trim();
+        field
+            .text
+            .parse()
+            .map_err(|error: T::Err| field.error(error.to_string()))
+    }
+
+    /// Parses an integer, signed or not depending on `T`.
+    pub fn int<T: FromStr>(&self) -> ParseResult<T> {
+        let field = self.trim();
+        field
+            .text
+            .parse()
+            .map_err(|_| field.error("Expected an integer"))
+    }
+
+    /// Parses a magnitude prefixed by a sign character, e.g. `L68` with `('L', 'R')`.
+    pub fn signed(&self, (negative, positive): (char, char)) -> ParseResult<i64> {
+        let field = self.trim();
+        let mut chars = field.text.chars();
+        let is_negative = match chars.next() {
+            Some(c) if c == negative => true,
+            Some(c) if c == positive => false,
+            _ => {
+                return Err(field.error(format!("Expected `{}` or `{}`", negative, positive)));
+            }
+        };
+        let magnitude_field = field.sub(chars.as_str());
+        let text = magnitude_field.text;
+        if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(magnitude_field.error("Expected digits after the sign"));
+        }
+        let magnitude: u64 = magnitude_field.int()?;
+        let value = if is_negative {
+            0_i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        };
+        value.ok_or_else(|| magnitude_field.error("Magnitude out of range"))
+    }
+
+    /// Parses an inclusive `a-b` range.
+    pub fn range<T: FromStr>(&self) -> ParseResult<RangeInclusive<T>> {
+        let field = self.trim();
+        // Skip the first character so that a negative start is not taken as the separator
+        let separator = field
+            .text
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-')
+            .map(|(idx, _)| idx)
+            .ok_or_else(|| field.error("Expected a range `a-b`"))?;
+        let start = field.sub(&field.text[..separator]).int()?;
+        let end = field.sub(&field.text[separator + 1..]).int()?;
+        Ok(start..=end)
+    }
+
+    /// Parses exactly `N` integers separated by `separator`, e.g. `x,y,z`.
+    pub fn tuple<T: FromStr + Default + Copy, const N: usize>(
+        &self,
+        separator: char,
+    ) -> ParseResult<[T; N]> {
+        let field = self.trim();
+        let parts: Vec<Field> = field.split(separator).collect();
+        if parts.len() != N {
+            return Err(field.error(format!("Expected {} values, found {}", N, parts.len())));
+        }
+        let mut result = [T::default(); N];
+        for (target, part) in result.iter_mut().zip(parts) {
+            *target = part.int()?;
+        }
+        Ok(result)
+    }
+
+    /// Parses `name: a b c` into the name and the whitespace-separated list.
+    pub fn adjacency(&self) -> ParseResult<(&'a str, Vec<&'a str>)> {
+        let (name, list) = self.split_once(':')?;
+        let name = name.trim();
+        if name.is_empty() || name.text.contains(char::is_whitespace) {
+            return Err(name.error("Expected a single name"));
+        }
+        Ok((name.text, list.text.split_whitespace().collect()))
+    }
+
+    /// Parses a string of decimal digits into their values.
+    pub fn digits(&self) -> ParseResult<Vec<u8>> {
+        let field = self.trim();
+        field
+            .text
+            .char_indices()
+            .map(|(idx, c)| {
+                c.to_digit(10).map(|digit| digit as u8).ok_or_else(|| {
+                    field
+                        .sub(&field.text[idx..idx + c.len_utf8()])
+                        .error("Expected a digit")
+                })
+            })
+            .collect()
+    }
+}
+
+/// The lines of `input`, numbered from 1.
+pub fn lines(input: &str) -> impl Iterator<Item = Field<'_>> {
+    Field::new(input).lines()
+}
+
+/// The non-empty lines of `input`, numbered by their position in the whole input.
+pub fn non_empty_lines(input: &str) -> impl Iterator<Item = Field<'_>> {
+    lines(input).filter(|line| !line.is_empty())
+}
+
+/// Blank-line separated sections of `input`.
+pub fn sections(input: &str) -> Vec<Field<'_>> {
+    Field::new(input).sections()
+}
+
+/// Parses a rectangular character map that may only contain characters of `allowed`.
+pub fn char_grid(input: &str, allowed: &str) -> ParseResult<Grid<char>> {
+    let mut width = None;
+    for line in lines(input) {
+        if let Some((idx, c)) = line
+            .text
+            .char_indices()
+            .find(|(_, c)| !allowed.contains(*c))
+        {
+            let cell = line.sub(&line.text[idx..idx + c.len_utf8()]);
+            return Err(cell.error(format!("Expected one of `{}`", allowed)));
+        }
+        let line_width = line.text.chars().count();
+        match width {
+            None => width = Some(line_width),
+            Some(width) if width != line_width => {
+                return Err(line.error(format!("Expected width {}, found {}", width, line_width)));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(Grid::parse(input))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_positions_of_derived_fields() {
+        let input = "a: b c\n\n  10-20, 30-x";
+        let line = lines(input).nth(2).unwrap();
+        assert_eq!((line.line, line.column), (3, 1));
+        let second = line.split(',').nth(1).unwrap().trim();
+        assert_eq!((second.line, second.column, second.text), (3, 10, "30-x"));
+        let error = second.range::<u64>().unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (3, 13, "x")
+        );
+        assert_eq!(
+            error.to_string(),
+            "Line 3, column 13: Expected an integer `x`"
+        );
+    }
+
+    #[test]
+    fn test_values() {
+        let field = Field::new(" 42 ");
+        assert_eq!(field.int::<u64>(), Ok(42));
+        assert!(Field::new("-4").int::<u64>().is_err());
+        assert_eq!(Field::new("-4").int::<i32>(), Ok(-4));
+        assert_eq!(Field::new("L68").signed(('L', 'R')), Ok(-68));
+        assert_eq!(Field::new("R5").signed(('L', 'R')), Ok(5));
+        assert_eq!(Field::new("X5").signed(('L', 'R')).unwrap_err().column, 1);
+        for (text, magnitude) in [("L-5", "-5"), ("L+5", "+5"), ("L 5", " 5"), ("R", "")] {
+            let error = Field::new(text).signed(('L', 'R')).unwrap_err();
+            assert_eq!((error.column, error.text.as_str()), (2, magnitude));
+        }
+        assert_eq!(
+            Field::new("L9223372036854775808").signed(('L', 'R')),
+            Ok(i64::MIN)
+        );
+        assert!(
+            Field::new("R9223372036854775808")
+                .signed(('L', 'R'))
+                .is_err()
+        );
+        assert_eq!(Field::new("11-22").range::<u64>(), Ok(11..=22));
+        assert_eq!(Field::new("-5--2").range::<i64>(), Ok(-5..=-2));
+        assert_eq!(
+            Field::new("162,817,812").tuple(','),
+            Ok([162_u64, 817, 812])
+        );
+        assert!(Field::new("1,2").tuple::<u64, 3>(',').is_err());
+        assert_eq!(Field::new("987").digits(), Ok(vec![9, 8, 7]));
+        let inner = Field::new(" (1,3)").delimited('(', ')').unwrap();
+        assert_eq!((inner.column, inner.text), (3, "1,3"));
+        assert!(Field::new("(1,3").delimited('(', ')').is_err());
+        assert_eq!(Field::new("9x7").digits().unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let line = Field::new("you: bbb ccc");
+        assert_eq!(line.adjacency(), Ok(("you", vec!["bbb", "ccc"])));
+        assert!(Field::new("you bbb").adjacency().is_err());
+        assert!(Field::new("a b: c").adjacency().is_err());
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "3-5\n10-14\n\n\n1\n5\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].text, "3-5\n10-14");
+        assert_eq!((sections[1].line, sections[1].text), (5, "1\n5"));
+        let lines: Vec<usize> = sections[1].lines().map(|line| line.line).collect();
+        assert_eq!(lines, [5, 6]);
+    }
+
+    #[test]
+    fn test_char_grid() {
+        let grid = char_grid(".@\n@.", ".@").unwrap();
+        assert_eq!(grid.find('@'), Some((1, 0)));
+        let error = char_grid(".@\n@#", ".@").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = char_grid(".@\n@", ".@").unwrap_err();
+        assert_eq!(error.message, "Expected width 2, found 1");
+    }
+}
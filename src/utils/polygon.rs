@@ -0,0 +1,240 @@
+use crate::utils::geometry::Point2;
+use crate::utils::interval_set::IntervalSet;
+
+type Point = Point2<i64>;
+
+/// A simple closed polygon given by its vertices in order, the last one connecting back to
+/// the first. Vertices are tile centres, and tiles on the boundary count as inside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        assert!(vertices.len() >= 3, "A polygon needs at least 3 vertices");
+        Polygon { vertices }
+    }
+
+    #[allow(dead_code, reason = "day 9 keeps its own list of red tiles")]
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Consecutive vertex pairs, including the closing edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Whether every edge is horizontal or vertical.
+    pub fn is_rectilinear(&self) -> bool {
+        self.edges().all(|(a, b)| a.x == b.x || a.y == b.y)
+    }
+
+    /// Twice the enclosed area, by the shoelace formula. Always an integer.
+    pub fn twice_area(&self) -> u64 {
+        let sum: i128 = self
+            .edges()
+            .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+            .sum();
+        sum.unsigned_abs() as u64
+    }
+
+    #[allow(dead_code, reason = "day 9 counts tiles, not continuous area")]
+    /// The enclosed area, rounded down for polygons with a half-integer area.
+    pub fn area(&self) -> u64 {
+        self.twice_area() / 2
+    }
+
+    /// Length of the boundary, measured along the axes. Exact for rectilinear polygons.
+    pub fn boundary_length(&self) -> u64 {
+        self.edges().map(|(a, b)| a.manhattan(&b) as u64).sum()
+    }
+
+    #[allow(
+        dead_code,
+        reason = "day 9 counts tiles per rectangle, not for the whole loop"
+    )]
+    /// Number of lattice points inside or on a rectilinear polygon, i.e. the number of
+    /// tiles it covers when vertices are tile centres (Pick's theorem).
+    pub fn covered_tiles(&self) -> u64 {
+        (self.twice_area() + self.boundary_length()) / 2 + 1
+    }
+
+    #[allow(dead_code, reason = "day 9 checks whole rectangles, not single tiles")]
+    pub fn contains(&self, point: Point) -> bool {
+        let Point2 { x, y } = point;
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            // On the boundary
+            let cross =
+                (b.x - a.x) as i128 * (y - a.y) as i128 - (x - a.x) as i128 * (b.y - a.y) as i128;
+            if cross == 0
+                && x >= a.x.min(b.x)
+                && x <= a.x.max(b.x)
+                && y >= a.y.min(b.y)
+                && y <= a.y.max(b.y)
+            {
+                return true;
+            }
+            // Ray cast towards positive x, counting edges that span `y` half-open
+            if (a.y > y) != (b.y > y) {
+                let lhs = (x - a.x) as i128 * (b.y - a.y) as i128;
+                let rhs = (y - a.y) as i128 * (b.x - a.x) as i128;
+                let crosses_right = if b.y > a.y { lhs < rhs } else { lhs > rhs };
+                if crosses_right {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Tiles of row `y` covered by a rectilinear polygon: those on the boundary, and those
+    /// inside it, which are the same as the ones inside it just below the row.
+    pub fn covered_row(&self, y: i64) -> IntervalSet<i64> {
+        let mut covered = IntervalSet::new();
+        let mut crossings = vec![];
+        for (a, b) in self.edges() {
+            let (lo_y, hi_y) = (a.y.min(b.y), a.y.max(b.y));
+            if lo_y <= y && y <= hi_y {
+                covered.insert(a.x.min(b.x)..=a.x.max(b.x));
+            }
+            if a.x == b.x && lo_y <= y && y < hi_y {
+                crossings.push(a.x);
+            }
+        }
+        crossings.sort_unstable();
+        for pair in crossings.chunks_exact(2) {
+            covered.insert(pair[0]..=pair[1]);
+        }
+        covered
+    }
+
+    /// The first tile, row by row, of the axis-aligned rectangle with opposite corners `a`
+    /// and `b` that a rectilinear polygon does not cover. Coverage only changes on rows
+    /// with vertices and the rows right after them, so only those are checked.
+    pub fn uncovered_tile(&self, a: Point, b: Point) -> Option<Point> {
+        debug_assert!(self.is_rectilinear());
+        let (x1, x2) = (a.x.min(b.x), a.x.max(b.x));
+        let (y1, y2) = (a.y.min(b.y), a.y.max(b.y));
+        let mut rows: Vec<i64> = self
+            .vertices
+            .iter()
+            .flat_map(|vertex| [vertex.y, vertex.y + 1])
+            .chain([y1])
+            .filter(|y| (y1..=y2).contains(y))
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        let rectangle_row: IntervalSet<i64> = [x1..=x2].into_iter().collect();
+        rows.into_iter().find_map(|y| {
+            let gap = rectangle_row
+                .difference(&self.covered_row(y))
+                .iter()
+                .next()?;
+            Some(Point::new(*gap.start(), y))
+        })
+    }
+
+    /// Whether a rectilinear polygon covers every tile of the axis-aligned rectangle with
+    /// opposite corners `a` and `b`. Boundary tiles count, so the rectangle may cross a seam
+    /// where two edges run side by side.
+    #[allow(dead_code, reason = "day 9 reports the first uncovered tile instead")]
+    pub fn contains_rectangle(&self, a: Point, b: Point) -> bool {
+        self.uncovered_tile(a, b).is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The red tile loop of the day 9 example.
+    fn example_loop() -> Polygon {
+        let vertices = [
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ];
+        Polygon::new(vertices.map(Point::from).to_vec())
+    }
+
+    #[test]
+    fn test_measurements() {
+        let polygon = example_loop();
+        assert!(polygon.is_rectilinear());
+        assert_eq!(polygon.area(), 4 * 2 + 9 * 2 + 2 * 2);
+        assert_eq!(polygon.boundary_length(), 4 + 6 + 2 + 2 + 7 + 2 + 5 + 2);
+        assert_eq!(polygon.covered_tiles(), 5 * 2 + 10 * 3 + 3 * 2);
+    }
+
+    #[test]
+    fn test_contains() {
+        let polygon = example_loop();
+        assert!(polygon.contains(Point::new(8, 2)));
+        assert!(polygon.contains(Point::new(7, 1)));
+        assert!(polygon.contains(Point::new(2, 4)));
+        assert!(polygon.contains(Point::new(10, 6)));
+        assert!(!polygon.contains(Point::new(3, 2)));
+        assert!(!polygon.contains(Point::new(8, 6)));
+        assert!(!polygon.contains(Point::new(12, 4)));
+    }
+
+    #[test]
+    fn test_contains_rectangle() {
+        let polygon = example_loop();
+        assert!(polygon.contains_rectangle(Point::new(9, 5), Point::new(2, 3)));
+        assert!(!polygon.contains_rectangle(Point::new(7, 1), Point::new(11, 7)));
+        assert!(polygon.contains_rectangle(Point::new(7, 3), Point::new(11, 1)));
+        assert!(!polygon.contains_rectangle(Point::new(2, 5), Point::new(11, 1)));
+        // All corners on the boundary, but the notch between them is outside
+        let u_shape = [
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (4, 4),
+            (4, 2),
+            (2, 2),
+            (2, 4),
+            (0, 4),
+        ];
+        let u_shape = Polygon::new(u_shape.map(Point::from).to_vec());
+        assert!(!u_shape.contains_rectangle(Point::new(2, 2), Point::new(4, 4)));
+        assert!(u_shape.contains_rectangle(Point::new(0, 0), Point::new(6, 2)));
+        // A notch one tile wide is only a seam, its two edges cover both columns
+        let seam = [
+            (0, 0),
+            (6, 0),
+            (6, 4),
+            (3, 4),
+            (3, 2),
+            (2, 2),
+            (2, 4),
+            (0, 4),
+        ];
+        let seam = Polygon::new(seam.map(Point::from).to_vec());
+        assert!(seam.contains_rectangle(Point::new(0, 0), Point::new(6, 4)));
+    }
+
+    #[test]
+    fn test_uncovered_tile() {
+        let polygon = example_loop();
+        assert_eq!(polygon.covered_row(4).iter().collect::<Vec<_>>(), [2..=11]);
+        assert_eq!(polygon.covered_row(6).iter().collect::<Vec<_>>(), [9..=11]);
+        assert_eq!(
+            polygon.uncovered_tile(Point::new(2, 5), Point::new(11, 1)),
+            Some(Point::new(2, 1))
+        );
+        assert_eq!(
+            polygon.uncovered_tile(Point::new(9, 5), Point::new(2, 3)),
+            None
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::ops::RangeInclusive;
+
+/// Matches numbers whose digits in `base` are a pattern repeated between `min_repeats` and
+/// `max_repeats` times, like 123123 or 0b101101.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepetitionRule {
+    min_repeats: u32,
+    max_repeats: u32,
+    base: u32,
+}
+
+impl RepetitionRule {
+    pub const fn exactly(repeats: u32) -> Self {
+        Self::between(repeats, repeats)
+    }
+
+    pub const fn at_least(repeats: u32) -> Self {
+        Self::between(repeats, u32::MAX)
+    }
+
+    pub const fn between(min_repeats: u32, max_repeats: u32) -> Self {
+        assert!(min_repeats >= 2, "A pattern has to repeat at least twice");
+        RepetitionRule {
+            min_repeats,
+            max_repeats,
+            base: 10,
+        }
+    }
+
+    #[allow(dead_code, reason = "day 2 IDs are all decimal")]
+    pub const fn in_base(self, base: u32) -> Self {
+        assert!(base >= 2, "A base needs at least two digits");
+        RepetitionRule { base, ..self }
+    }
+
+    pub fn allows(&self, repeats: u32) -> bool {
+        (self.min_repeats..=self.max_repeats).contains(&repeats)
+    }
+
+    /// Shortest pattern length that repeats an allowed number of times to spell `number`.
+    pub fn matched_period(&self, number: u64) -> Option<u32> {
+        let digits = self.digits(number);
+        let length = digits.len() as u32;
+        (1..length)
+            .filter(|period| length.is_multiple_of(*period) && self.allows(length / period))
+            .find(|&period| {
+                let period = period as usize;
+                digits
+                    .chunks(period)
+                    .all(|chunk| chunk == &digits[..period])
+            })
+    }
+
+    fn digits(&self, mut number: u64) -> Vec<u32> {
+        let base = self.base as u64;
+        let mut digits = vec![];
+        loop {
+            digits.push((number % base) as u32);
+            number /= base;
+            if number == 0 {
+                break;
+            }
+        }
+        digits.reverse();
+        digits
+    }
+
+    fn digit_count(&self, number: u64) -> u32 {
+        number.checked_ilog(self.base as u64).unwrap_or(0) + 1
+    }
+
+    fn power(&self, exponent: u32) -> u128 {
+        (self.base as u128).pow(exponent)
+    }
+
+    /// The number that repeats a `period` digit pattern to `length` digits, e.g. 10101 for
+    /// a 2 digit pattern over 6 decimal digits, so that every such number is pattern * multiplier.
+    fn multiplier(&self, length: u32, period: u32) -> u128 {
+        (self.power(length) - 1) / (self.power(period) - 1)
+    }
+
+    /// Range of `period` digit patterns whose repetition to `length` digits lies in `lo..=hi`.
+    fn patterns(&self, lo: u64, hi: u64, length: u32, period: u32) -> RangeInclusive<u128> {
+        let multiplier = self.multiplier(length, period);
+        let first = (lo as u128)
+            .div_ceil(multiplier)
+            .max(self.power(period - 1));
+        let last = (hi as u128 / multiplier).min(self.power(period) - 1);
+        first..=last
+    }
+
+    /// Sum of the `length` digit numbers in `lo..=hi` that repeat a `period` digit pattern.
+    fn periodic_sum(&self, lo: u64, hi: u64, length: u32, period: u32) -> u128 {
+        let patterns = self.patterns(lo, hi, length, period);
+        if patterns.is_empty() {
+            return 0;
+        }
+        let (first, last) = (*patterns.start(), *patterns.end());
+        let pattern_sum = (first + last) * (last - first + 1) / 2;
+        pattern_sum * self.multiplier(length, period)
+    }
+
+    /// `lo..=hi` cut down to the numbers with `length` digits.
+    fn clamp(&self, lo: u64, hi: u64, length: u32) -> (u64, u64) {
+        let smallest = self.power(length - 1) as u64;
+        let largest = (self.power(length) - 1).min(u64::MAX as u128) as u64;
+        (lo.max(smallest), hi.min(largest))
+    }
+
+    /// Sum of the matching numbers in `range`, without visiting them. A number is counted under
+    /// every period that is a multiple of its shortest one, so the sums are first reduced
+    /// to numbers by shortest period, and one whose shortest pattern repeats `m` times is
+    /// a match if some allowed repeat count divides `m`.
+    pub fn sum_in(&self, range: &RangeInclusive<u64>) -> u128 {
+        if range.is_empty() {
+            return 0;
+        }
+        let (lo, hi) = (*range.start(), *range.end());
+        let mut total = 0;
+        for length in self.digit_count(lo)..=self.digit_count(hi) {
+            let (lo, hi) = self.clamp(lo, hi, length);
+            let periods: Vec<u32> = (1..=length).filter(|p| length % p == 0).collect();
+            let mut by_shortest_period: Vec<u128> = vec![];
+            for (idx, &period) in periods.iter().enumerate() {
+                let shorter: u128 = periods[..idx]
+                    .iter()
+                    .zip(&by_shortest_period)
+                    .filter(|(shorter, _)| period % *shorter == 0)
+                    .map(|(_, sum)| sum)
+                    .sum();
+                let sum = self.periodic_sum(lo, hi, length, period) - shorter;
+                by_shortest_period.push(sum);
+                let max_repeats = length / period;
+                if (2..=max_repeats).any(|k| max_repeats % k == 0 && self.allows(k)) {
+                    total += sum;
+                }
+            }
+        }
+        total
+    }
+
+    /// The matching numbers in `range` in increasing order, generated from their patterns.
+    pub fn matches_in(&self, range: &RangeInclusive<u64>) -> Vec<u64> {
+        if range.is_empty() {
+            return vec![];
+        }
+        let (lo, hi) = (*range.start(), *range.end());
+        let mut matches = vec![];
+        for length in self.digit_count(lo)..=self.digit_count(hi) {
+            let (lo, hi) = self.clamp(lo, hi, length);
+            for period in (1..length).filter(|p| length % p == 0 && self.allows(length / p)) {
+                let multiplier = self.multiplier(length, period);
+                matches.extend(
+                    self.patterns(lo, hi, length, period)
+                        .map(|pattern| (pattern * multiplier) as u64),
+                );
+            }
+        }
+        matches.sort_unstable();
+        matches.dedup();
+        matches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matched_period() {
+        assert_eq!(RepetitionRule::exactly(2).matched_period(123123), Some(3));
+        assert_eq!(RepetitionRule::at_least(2).matched_period(121212), Some(2));
+        assert_eq!(RepetitionRule::exactly(2).matched_period(121212), None);
+        assert_eq!(RepetitionRule::at_least(2).matched_period(1111), Some(1));
+        // Two repeats of 11 rather than four of 1
+        assert_eq!(RepetitionRule::exactly(2).matched_period(1111), Some(2));
+        assert_eq!(RepetitionRule::exactly(3).matched_period(1111), None);
+        assert_eq!(RepetitionRule::between(3, 4).matched_period(7777), Some(1));
+        assert_eq!(RepetitionRule::at_least(2).matched_period(7), None);
+        // 0b101101 and 0x1f1f
+        assert_eq!(
+            RepetitionRule::exactly(2).in_base(2).matched_period(45),
+            Some(3)
+        );
+        assert_eq!(
+            RepetitionRule::exactly(2)
+                .in_base(16)
+                .matched_period(0x1f1f),
+            Some(2)
+        );
+        assert_eq!(
+            RepetitionRule::exactly(2).in_base(16).matched_period(1010),
+            None
+        );
+    }
+
+    #[test]
+    fn test_enumeration_matches_brute_force() {
+        let rules = [
+            RepetitionRule::exactly(2),
+            RepetitionRule::at_least(2),
+            RepetitionRule::exactly(3),
+            RepetitionRule::between(2, 3),
+            RepetitionRule::at_least(2).in_base(2),
+            RepetitionRule::exactly(3).in_base(7),
+        ];
+        let ranges = [
+            1..=19_999,
+            95..=115,
+            998..=1012,
+            999_000..=1_001_099,
+            u64::MAX - 100..=u64::MAX,
+        ];
+        for range_ in ranges {
+            for rule in rules {
+                let expected: Vec<u64> = range_
+                    .clone()
+                    .filter(|id| rule.matched_period(*id).is_some())
+                    .collect();
+                assert_eq!(rule.matches_in(&range_), expected);
+                let sum = expected.iter().map(|id| *id as u128).sum::<u128>();
+                assert_eq!(rule.sum_in(&range_), sum);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_ranges() {
+        // 11 to 99, and the whole u64 domain without overflowing
+        assert_eq!(
+            RepetitionRule::exactly(2).sum_in(&(10..=99)),
+            11 * (1..=9).sum::<u128>()
+        );
+        for base in [2, 10, 36] {
+            let everything = RepetitionRule::at_least(2)
+                .in_base(base)
+                .sum_in(&(1..=u64::MAX));
+            assert!(
+                everything
+                    > RepetitionRule::exactly(2)
+                        .in_base(base)
+                        .sum_in(&(1..=u64::MAX))
+            );
+        }
+        // u64::MAX is no repetition, so the range may end there or just before it
+        assert_eq!(
+            RepetitionRule::at_least(2).sum_in(&(1..=u64::MAX)),
+            RepetitionRule::at_least(2).sum_in(&(1..=u64::MAX - 1))
+        );
+        assert_eq!(
+            RepetitionRule::at_least(2).sum_in(&RangeInclusive::new(2, 1)),
+            0
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use disjoint::DisjointSet;
+
+/// A disjoint set over `0..len` that keeps the number of components and the size of each
+/// component up to date on every join.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    sets: DisjointSet,
+    /// Size of the component of each root, stale for elements that are no longer a root
+    sizes: Vec<usize>,
+    component_count: usize,
+}
+
+impl UnionFind {
+    pub fn with_len(len: usize) -> Self {
+        UnionFind {
+            sets: DisjointSet::with_len(len),
+            sizes: vec![1; len],
+            component_count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    #[allow(dead_code, reason = "pairs with `len`, day 8 is never empty")]
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Merges the components of `a` and `b`, returns whether they were separate before.
+    pub fn join(&mut self, a: usize, b: usize) -> bool {
+        let size = self.size_of(a) + self.size_of(b);
+        if !self.sets.join(a, b) {
+            return false;
+        }
+        self.sizes[self.sets.root_of(a)] = size;
+        self.component_count -= 1;
+        true
+    }
+
+    #[allow(dead_code, reason = "day 8 learns about merges from `join` itself")]
+    pub fn is_joined(&self, a: usize, b: usize) -> bool {
+        self.sets.is_joined(a, b)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    /// Size of the component containing `element`.
+    pub fn size_of(&self, element: usize) -> usize {
+        self.sizes[self.sets.root_of(element)]
+    }
+
+    /// Sizes of the `k` largest components, largest first.
+    pub fn largest_components(&self, k: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&element| self.sets.root_of(element) == element)
+            .map(|root| self.sizes[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.truncate(k);
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_join_tracks_counts_and_sizes() {
+        let mut sets = UnionFind::with_len(6);
+        assert_eq!(sets.component_count(), 6);
+        assert!(sets.join(0, 1));
+        assert!(sets.join(1, 2));
+        assert!(!sets.join(2, 0));
+        assert!(sets.join(3, 4));
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.size_of(2), 3);
+        assert_eq!(sets.size_of(4), 2);
+        assert_eq!(sets.size_of(5), 1);
+        assert!(sets.is_joined(0, 2));
+        assert!(!sets.is_joined(0, 3));
+    }
+
+    #[test]
+    fn test_largest_components() {
+        let mut sets = UnionFind::with_len(7);
+        for (a, b) in [(0, 1), (2, 3), (3, 4), (4, 5)] {
+            sets.join(a, b);
+        }
+        assert_eq!(sets.largest_components(2), [4, 2]);
+        assert_eq!(sets.largest_components(10), [4, 2, 1]);
+        sets.join(1, 6);
+        sets.join(6, 5);
+        assert_eq!(sets.largest_components(3), [7]);
+        assert_eq!(sets.component_count(), 1);
+    }
+}